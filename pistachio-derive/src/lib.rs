@@ -14,6 +14,10 @@ type UnitFields = Punctuated<syn::Field, syn::Token![,]>;
 struct Field {
     key: String,
     field: TokenStream2,
+    /// A reference to the field as a `Render` value, which differs from
+    /// `&self.#field` when it's rendered via its `Display` or `Debug`
+    /// implementation.
+    value: TokenStream2,
     callback: Option<syn::Path>,
 }
 
@@ -43,6 +47,8 @@ struct Pistachio {
     flatten: Option<()>,
     rename: Option<syn::LitStr>,
     callback: Option<syn::Path>,
+    display: Option<()>,
+    debug: Option<()>,
 }
 
 #[proc_macro_derive(Render, attributes(pistachio))]
@@ -68,6 +74,7 @@ pub fn derive_render(input: TokenStream) -> TokenStream {
             let mut callback = None;
             let mut rename = None;
             let mut skip = false;
+            let mut wrapper = None;
 
             match Pistachio::try_from_attributes(&field.attrs) {
                 Ok(Some(pistachio)) => {
@@ -95,6 +102,14 @@ pub fn derive_render(input: TokenStream) -> TokenStream {
                     if let Some(path) = pistachio.callback {
                         callback = Some(path);
                     }
+
+                    if pistachio.display.is_some() {
+                        wrapper = Some(quote!(::pistachio::render::Displayed));
+                    }
+
+                    if pistachio.debug.is_some() {
+                        wrapper = Some(quote!(::pistachio::render::Debugged));
+                    }
                 },
                 Ok(None) => (),
                 Err(err) => errors.push(err),
@@ -120,9 +135,15 @@ pub fn derive_render(input: TokenStream) -> TokenStream {
                 },
             );
 
+            let value = match wrapper {
+                Some(wrapper) => quote!(#wrapper::from_ref(&self.#field)),
+                None => quote!(&self.#field),
+            };
+
             Some(Field {
                 key,
                 field,
+                value,
                 callback,
             })
         })
@@ -140,58 +161,66 @@ pub fn derive_render(input: TokenStream) -> TokenStream {
 
     fields.sort_unstable();
 
-    let render_named_escaped = fields.iter().map(
-        |Field {
-             key,
-             field,
-             callback,
-             ..
-         }| {
-            if let Some(callback) = callback {
-                quote! {
-                    #key => #callback(&self.#field, context, writer).map(|_| true),
-                }
-            } else {
-                quote! {
-                    #key => self.#field.render_escaped(context, writer).map(|_| true),
-                }
-            }
-        },
-    );
-
-    let render_named_unescaped = fields.iter().map(
-        |Field {
-             key,
-             field,
-             callback,
-             ..
-         }| {
-            if let Some(callback) = callback {
-                quote! {
-                    #key => #callback(&self.#field, context, writer).map(|_| true),
-                }
-            } else {
-                quote! {
-                    #key => self.#field.render_unescaped(context, writer).map(|_| true),
-                }
-            }
-        },
-    );
-
-    let render_named_section = fields.iter().map(|Field { key, field, .. }| {
+    let lookup = fields.iter().map(|Field { key, value, .. }| {
         quote! {
-            #key => self.#field.render_section(context, writer).map(|_| true),
+            #key => Some(#value),
         }
     });
 
-    let render_named_inverted = fields.iter().map(|Field { key, field, .. }| {
-        quote! {
-            #key => self.#field.render_inverted(context, writer).map(|_| true),
-        }
-    });
+    // Fields with a callback render `{{name}}` tags themselves, and so
+    // override the default `render_named_*` methods.
+    let callbacks = fields
+        .iter()
+        .filter_map(
+            |Field {
+                 key,
+                 field,
+                 callback,
+                 ..
+             }| {
+                callback.as_ref().map(|callback| {
+                    quote! {
+                        [#key] => #callback(&self.#field, context, writer).map(|_| true),
+                    }
+                })
+            },
+        )
+        .collect::<Vec<_>>();
+
+    let render_named_callbacks = if callbacks.is_empty() {
+        quote!()
+    } else {
+        let named = [
+            (quote!(render_named_escaped), quote!(render_escaped)),
+            (quote!(render_named_unescaped), quote!(render_unescaped)),
+        ];
+
+        let named = named.iter().map(|(named, method)| {
+            quote! {
+                #[inline]
+                fn #named(
+                    &self,
+                    name: &[&str],
+                    context: ::pistachio::render::Context,
+                    writer: &mut ::pistachio::render::Writer
+                ) -> std::result::Result<bool, ::pistachio::Error> {
+                    match name {
+                        #( #callbacks )*
+                        [] => self.#method(context, writer).map(|_| true),
+                        [key, name @ ..] => match ::pistachio::render::Render::lookup(self, key) {
+                            Some(value) => value.#named(name, context.push(value), writer),
+                            None => Ok(false),
+                        },
+                    }
+                }
+            }
+        });
+
+        quote!(#( #named )*)
+    };
 
     let flatten = &*flatten;
-    let fields = fields.iter().map(|Field { field, .. }| field);
+    let values = fields.iter().map(|Field { value, .. }| value);
 
     let where_clause = type_params
         .map(|param| quote!(#param: ::pistachio::render::Render))
@@ -206,7 +235,7 @@ pub fn derive_render(input: TokenStream) -> TokenStream {
         impl #generics ::pistachio::render::Render for #ident #generics #where_clause {
             #[inline]
             fn size_hint(&self, template: &::pistachio::Template) -> usize {
-                template.size_hint() #( + self.#fields.size_hint(template) )*
+                template.size_hint() #( + #values.size_hint(template) )*
             }
 
             #[inline]
@@ -219,84 +248,14 @@ pub fn derive_render(input: TokenStream) -> TokenStream {
             }
 
             #[inline]
-            fn render_named_escaped(
-                &self,
-                name: &[&str],
-                context: ::pistachio::render::Context,
-                writer: &mut ::pistachio::render::Writer
-            ) -> std::result::Result<bool, ::pistachio::Error> {
-                if name.is_empty() {
-                    return Ok(false)
-                }
-
-                match name[0] {
-                    #( #render_named_escaped )*
-                    _ => Ok(
-                        #( self.#flatten.render_named_escaped(&name[1..], context, writer)? ||)*
-                        false
-                    )
-                }
-            }
-
-            #[inline]
-            fn render_named_unescaped(
-                &self,
-                name: &[&str],
-                context: ::pistachio::render::Context,
-                writer: &mut ::pistachio::render::Writer
-            ) -> std::result::Result<bool, ::pistachio::Error> {
-                if name.is_empty() {
-                    return Ok(false)
-                }
-
-                match name[0] {
-                    #( #render_named_unescaped )*
-                    _ => Ok(
-                        #( self.#flatten.render_named_unescaped(&name[1..], context, writer)? ||)*
-                        false
-                    )
-                }
-            }
-
-            #[inline]
-            fn render_named_section(
-                &self,
-                name: &[&str],
-                context: ::pistachio::render::Context,
-                writer: &mut ::pistachio::render::Writer
-            ) -> std::result::Result<bool, ::pistachio::Error> {
-                if name.is_empty() {
-                    return Ok(false)
-                }
-
-                match name[0] {
-                    #( #render_named_section )*
-                    _ => Ok(
-                        #( self.#flatten.render_named_section(&name[1..], context, writer)? ||)*
-                        false
-                    )
+            fn lookup(&self, key: &str) -> Option<&dyn ::pistachio::render::Render> {
+                match key {
+                    #( #lookup )*
+                    _ => None #( .or_else(|| ::pistachio::render::Render::lookup(&self.#flatten, key)) )*,
                 }
             }
 
-            #[inline]
-            fn render_named_inverted(
-                &self,
-                name: &[&str],
-                context: ::pistachio::render::Context,
-                writer: &mut ::pistachio::render::Writer
-            ) -> std::result::Result<bool, ::pistachio::Error> {
-                if name.is_empty() {
-                    return Ok(false)
-                }
-
-                match name[0] {
-                    #( #render_named_inverted )*
-                    _ => Ok(
-                        #( self.#flatten.render_named_inverted(&name[1..], context, writer)? ||)*
-                        false
-                    )
-                }
-            }
+            #render_named_callbacks
         }
     };
 
@@ -18,7 +18,7 @@ struct Vars<'a> {
 fn main() {
     match Pistachio::builder().directory("./examples").build() {
         Err(err) => println!("foo: {:#?}", err),
-        Ok(mut pistachio) => match pistachio.get("hello-world") {
+        Ok(pistachio) => match pistachio.get("hello-world") {
             Err(err) => println!("{}", err),
             Ok(template) => {
                 println!("----------");
@@ -0,0 +1,240 @@
+//! A runner for test suites in the [mustache spec] format, which reports the
+//! outcome of every test rather than stopping at the first failure.
+//!
+//! Besides the official spec, this can be used to run your own template
+//! regression suites, written in the same format.
+//!
+//! [mustache spec]: https://github.com/mustache/spec
+
+use std::{
+    collections::BTreeMap,
+    ffi::OsStr,
+    fmt,
+    fs,
+    path::Path,
+};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{
+    render::Render,
+    Error,
+    Pistachio,
+};
+
+/// A suite of tests, as deserialized from a spec file.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Spec {
+    #[serde(default)]
+    pub overview: String,
+    pub tests: Vec<Test>,
+}
+
+/// A single test case, consisting of a template and the output it's expected
+/// to produce when rendered with `data`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Test {
+    pub name: String,
+    #[serde(default)]
+    pub desc: String,
+    #[serde(default)]
+    pub data: Value,
+    pub template: String,
+    #[serde(default)]
+    pub partials: BTreeMap<String, String>,
+    pub expected: String,
+}
+
+impl Spec {
+    /// Load a spec file, which is parsed as YAML if it has a `.yml` or `.yaml`
+    /// extension and the `serde_yaml` feature is enabled, otherwise as JSON.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Spec, Error> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(Error::Io)?;
+
+        match path.extension().and_then(OsStr::to_str) {
+            #[cfg(feature = "serde_yaml")]
+            Some("yml" | "yaml") => Spec::from_yaml(&source),
+            _ => Spec::from_json(&source),
+        }
+    }
+
+    pub fn from_json(source: &str) -> Result<Spec, Error> {
+        serde_json::from_str(source).map_err(|err| Error::InvalidSpec(err.to_string()))
+    }
+
+    #[cfg(feature = "serde_yaml")]
+    pub fn from_yaml(source: &str) -> Result<Spec, Error> {
+        serde_yaml::from_str(source).map_err(|err| Error::InvalidSpec(err.to_string()))
+    }
+
+    /// Run every test, rendering templates with the test's `data`.
+    pub fn run(&self) -> Report {
+        self.run_with(|test| Box::new(test.data.clone()))
+    }
+
+    /// Run every test, rendering templates with the variables returned by `vars`.
+    /// This allows substituting values that can't be described by the spec's
+    /// data, such as lambdas.
+    pub fn run_with<F>(&self, vars: F) -> Report
+    where
+        F: Fn(&Test) -> Box<dyn Render>,
+    {
+        Report {
+            outcomes: self
+                .tests
+                .iter()
+                .map(|test| test.run(&vars(test)))
+                .collect(),
+        }
+    }
+}
+
+impl Test {
+    /// Render the template with `vars`, using a fresh `Pistachio` containing
+    /// only this test's partials.
+    pub fn run(&self, vars: &dyn Render) -> Outcome {
+        let status = match self.render(vars) {
+            Ok(actual) if actual == self.expected => Status::Passed,
+            Ok(actual) => Status::Failed {
+                expected: self.expected.clone(),
+                actual,
+            },
+            Err(err) => Status::Errored(err),
+        };
+
+        Outcome {
+            name: self.name.clone(),
+            status,
+        }
+    }
+
+    fn render(&self, vars: &dyn Render) -> Result<String, Error> {
+        let pistachio = Pistachio::builder()
+            .in_memory()
+            .missing_is_false()
+            .build()?;
+
+        for (name, source) in &self.partials {
+            pistachio.add(name.clone(), source.clone())?;
+        }
+
+        // The template itself isn't added, so it can't shadow a partial.
+        pistachio
+            .inner
+            .parse_template(self.template.clone().into())?
+            .render(&vars)
+    }
+}
+
+/// The outcome of every test in a spec, in order.
+#[derive(Debug)]
+pub struct Report {
+    pub outcomes: Vec<Outcome>,
+}
+
+impl Report {
+    pub fn passed(&self) -> usize {
+        self.outcomes
+            .iter()
+            .filter(|outcome| outcome.is_success())
+            .count()
+    }
+
+    pub fn failed(&self) -> usize {
+        self.outcomes.len() - self.passed()
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.outcomes.iter().all(Outcome::is_success)
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &Outcome> {
+        self.outcomes.iter().filter(|outcome| !outcome.is_success())
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for outcome in &self.outcomes {
+            write!(f, "{}", outcome)?;
+        }
+
+        writeln!(f, "{} passed, {} failed", self.passed(), self.failed())
+    }
+}
+
+#[derive(Debug)]
+pub struct Outcome {
+    pub name: String,
+    pub status: Status,
+}
+
+impl Outcome {
+    pub fn is_success(&self) -> bool {
+        matches!(self.status, Status::Passed)
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.status {
+            Status::Passed => writeln!(f, "PASS {}", self.name),
+            Status::Failed { .. } => {
+                writeln!(f, "FAIL {}", self.name)?;
+                write!(f, "{}", self.status)
+            },
+            Status::Errored(_) => {
+                writeln!(f, "ERROR {}", self.name)?;
+                write!(f, "{}", self.status)
+            },
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Status {
+    Passed,
+
+    /// The template rendered, but not as expected.
+    Failed {
+        expected: String,
+        actual: String,
+    },
+
+    /// The template couldn't be parsed or rendered.
+    Errored(Error),
+}
+
+// Display the lines of a failure prefixed by `-` if expected and `+` if
+// actually rendered. Lines are debug formatted so whitespace is visible.
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Status::Passed => Ok(()),
+            Status::Failed { expected, actual } => {
+                let expected = expected.split_inclusive('\n').collect::<Vec<_>>();
+                let actual = actual.split_inclusive('\n').collect::<Vec<_>>();
+
+                for index in 0..expected.len().max(actual.len()) {
+                    match (expected.get(index), actual.get(index)) {
+                        (Some(a), Some(b)) if a == b => writeln!(f, "    {:?}", a)?,
+                        (a, b) => {
+                            if let Some(a) = a {
+                                writeln!(f, "  - {:?}", a)?;
+                            }
+
+                            if let Some(b) = b {
+                                writeln!(f, "  + {:?}", b)?;
+                            }
+                        },
+                    }
+                }
+
+                Ok(())
+            },
+            Status::Errored(err) => writeln!(f, "    {}", err),
+        }
+    }
+}
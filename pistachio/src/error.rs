@@ -20,11 +20,32 @@ pub enum Error {
     /// An attempt to include a partial or parent failed.
     InvalidPartial(String),
 
+    /// A template, partial or parent with the given name couldn't be found.
+    MissingTemplate(String),
+
     /// An LR parser error occurred.
     ParsingFailed((usize, usize), String),
 
     /// A variable wasn't found on the stack and raising errors is enabled.
     MissingVariable((usize, usize), String),
+
+    /// A `Mutex` or `RwLock` couldn't be locked for rendering, because it's
+    /// held elsewhere or by an enclosing section rendered through it, or a
+    /// `RefCell` is mutably borrowed.
+    LockUnavailable,
+
+    /// A conformance spec file couldn't be deserialized.
+    #[cfg(feature = "conformance")]
+    InvalidSpec(String),
+
+    /// A date or time format is invalid, or requires components the value
+    /// doesn't have.
+    #[cfg(any(feature = "chrono", feature = "time"))]
+    InvalidFormat(String),
+
+    /// A `Serialize` impl failed while being rendered.
+    #[cfg(feature = "serde")]
+    SerializationFailed(String),
 }
 
 impl Error {
@@ -87,12 +108,20 @@ impl fmt::Display for Error {
                 f.write_str("loading templates from the filesystem is disabled")
             },
             Error::InvalidPartial(msg) => write!(f, "partial path {} is invalid", msg),
+            Error::MissingTemplate(name) => write!(f, "template {} not found", name),
             Error::ParsingFailed(_span, msg) => f.write_str(msg),
             Error::MissingVariable(span, ident) => write!(
                 f,
                 "missing variable `{{{{{}}}}}` at position {:?}",
                 ident, span
             ),
+            Error::LockUnavailable => f.write_str("a lock or cell being rendered is already held"),
+            #[cfg(feature = "conformance")]
+            Error::InvalidSpec(msg) => write!(f, "invalid spec: {}", msg),
+            #[cfg(any(feature = "chrono", feature = "time"))]
+            Error::InvalidFormat(msg) => write!(f, "invalid format: {}", msg),
+            #[cfg(feature = "serde")]
+            Error::SerializationFailed(msg) => write!(f, "serialization failed: {}", msg),
         }
     }
 }
@@ -27,23 +27,23 @@ use crate::Error;
 
 #[derive(Debug, Clone, Copy)]
 pub enum Token<'a> {
-    Eof(&'a str, Layout),    // Raw textual content leading up to EOF.
-    Ident(&'a str),          // An identifier - the single component of a key, no dots!
-    String(&'a str),         // A string inside a tag.
-    Enter(&'a str, &'a str), // `{{` tag start
-    Leave(&'a str, Layout),  // `}}` tag end
-    RSlash,                  // `/`
-    Hash,                    // #
-    Caret,                   // ^
-    LAngle,                  // <
-    RAngle,                  // >
-    LBrace,                  // {
-    Dollar,                  // $
-    Bang,                    // !
-    Ampersand,               // &
-    Asterisk,                // *
-    Period,                  // .
-    Equals,                  // =
+    Eof(&'a str, Layout),           // Raw textual content leading up to EOF.
+    Ident(&'a str),                 // An identifier - the single component of a key, no dots!
+    String(&'a str),                // A string inside a tag.
+    Enter(&'a str, Delimiters<'a>), // `{{` tag start
+    Leave(&'a str, Layout),         // `}}` tag end
+    RSlash,                         // `/`
+    Hash,                           // #
+    Caret,                          // ^
+    LAngle,                         // <
+    RAngle,                         // >
+    LBrace,                         // {
+    Dollar,                         // $
+    Bang,                           // !
+    Ampersand,                      // &
+    Asterisk,                       // *
+    Period,                         // .
+    Equals,                         // =
 }
 
 use std::fmt;
@@ -56,7 +56,7 @@ impl fmt::Display for Token<'_> {
             Eof(s, _) => write!(f, "{}", s),
             Ident(s) => write!(f, "{}", s),
             String(s) => write!(f, "{}", s),
-            Enter(s, d) => write!(f, "{}{}", s, d),
+            Enter(s, d) => write!(f, "{}{}", s, d.open),
             Leave(d, _) => write!(f, "{}", d),
             RSlash => write!(f, "/"),
             Hash => write!(f, "#"),
@@ -89,6 +89,29 @@ impl Layout {
             Layout::Standalone => text.trim_end_matches(&[' ']),
         }
     }
+
+    /// The whitespace removed by [`Layout::trim`], which standalone partials
+    /// use to indent every line of the included template.
+    pub fn indent(self, text: &str) -> &str {
+        &text[self.trim(text).len()..]
+    }
+}
+
+/// The tag delimiters, which default to `{{` and `}}` and can be changed
+/// mid-template by a `{{=<% %>=}}` tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Delimiters<'a> {
+    pub open: &'a str,
+    pub close: &'a str,
+}
+
+impl Default for Delimiters<'_> {
+    fn default() -> Self {
+        Self {
+            open: "{{",
+            close: "}}",
+        }
+    }
 }
 
 enum Braces {
@@ -116,6 +139,12 @@ pub struct Lexer<'a> {
     previous: Option<Token<'a>>,
     braces: Braces,
     layout: Layout,
+    delimiters: Delimiters<'a>,
+    /// Delimiters set by a `{{=<% %>=}}` tag, which take effect once it's closed.
+    pending: Option<Delimiters<'a>>,
+    /// Whether each open section is closed by a path rather than a dotted
+    /// name, as a `{{<parent}}` tag is, innermost last.
+    literal_closing: Vec<bool>,
 }
 
 impl<'a> Iterator for Lexer<'a> {
@@ -127,7 +156,7 @@ impl<'a> Iterator for Lexer<'a> {
 }
 
 impl<'a> Lexer<'a> {
-    pub fn new(source: &'a str) -> Lexer<'a> {
+    pub fn with_delimiters(source: &'a str, delimiters: Delimiters<'a>) -> Lexer<'a> {
         Lexer {
             source,
             position: 0,
@@ -135,6 +164,9 @@ impl<'a> Lexer<'a> {
             previous: None,
             braces: Braces::Two,
             layout: Layout::Standalone,
+            delimiters,
+            pending: None,
+            literal_closing: Vec::new(),
         }
     }
 
@@ -165,20 +197,19 @@ impl<'a> Lexer<'a> {
 
         let layout = self.layout;
         let start = self.position;
+        let delimiters = self.delimiters;
+        let Delimiters { open, close } = delimiters;
         let mut token = match self.mode {
             Mode::Content => {
                 scan! {
-                    self.until_inclusive("{{", Enter)
+                    self.until_inclusive(open, |text, _| Enter(text, delimiters))
                     self.drain(|s| Eof(s, layout))
                 }
             },
 
             Mode::Control => {
                 scan! {
-                    match self.braces {
-                        Braces::Three => self.token("}}}", |d| Leave(d, layout)),
-                        Braces::Two   => self.token("}}",  |d| Leave(d, layout)),
-                    }
+                    self.leave(layout)
 
                     self.token("/",  |_| RSlash)
                     self.token("#",  |_| Hash)
@@ -192,14 +223,14 @@ impl<'a> Lexer<'a> {
                     self.token("!",  |_| Bang)
                     self.token("&",  |_| Ampersand)
                     self.token("{",  |_| LBrace)
-                    self.until(&['.', ' ', '{', '}'], Ident)
+                    self.ident()
                 }
             },
 
             Mode::Literal => {
                 scan! {
-                    self.token("}}", |d| Leave(d, layout))
-                    self.until("}}", String)
+                    self.token(close, |d| Leave(d, layout))
+                    self.until(close, String)
                 }
             },
         }?;
@@ -214,7 +245,7 @@ impl<'a> Lexer<'a> {
 
         match token {
             // {{
-            Enter(content, _delim) => {
+            Enter(content, _delimiters) => {
                 // XXX: it would be safe to iterate over bytes here.
                 // Was there a newline + any amount of whitspace prior to the enter tag?
                 for c in content.chars().rev() {
@@ -244,17 +275,47 @@ impl<'a> Lexer<'a> {
                 self.braces = Braces::Two;
                 // self.layout = Layout::Preserve;
                 self.mode = Mode::Content;
+
+                if let Some(delimiters) = self.pending.take() {
+                    self.delimiters = delimiters;
+                }
             },
-            // {{/ | {{!
-            RSlash | Bang if enter => {
+            // {{!
+            Bang if enter => {
                 self.skip_whitespace();
                 self.mode = Mode::Literal;
             },
-            // {{> | {{<
-            RAngle | LAngle if enter && !dynamic => {
+            // {{=
+            Equals if enter => {
+                self.pending = Some(self.set_delimiters(start)?);
                 self.skip_whitespace();
                 self.mode = Mode::Literal;
             },
+            // {{# | {{^ | {{$
+            Hash | Caret | Dollar if enter => {
+                self.literal_closing.push(false);
+                self.skip_whitespace();
+            },
+            // {{<
+            LAngle if enter => {
+                self.literal_closing.push(!dynamic);
+                self.skip_whitespace();
+                if !dynamic {
+                    self.mode = Mode::Literal;
+                }
+            },
+            // {{>
+            RAngle if enter && !dynamic => {
+                self.skip_whitespace();
+                self.mode = Mode::Literal;
+            },
+            // {{/
+            RSlash if enter => {
+                self.skip_whitespace();
+                if self.literal_closing.pop() == Some(true) {
+                    self.mode = Mode::Literal;
+                }
+            },
             // {{{
             LBrace if enter => {
                 self.skip_whitespace();
@@ -282,6 +343,70 @@ impl<'a> Lexer<'a> {
 
     /// XXX: panic if patterns are empty, otherwise the lexer won't be productive.
 
+    /// Consume the close delimiter, which is preceeded by `}` for `{{{unescaped}}}` tags.
+    fn leave(&mut self, layout: Layout) -> Option<Token<'a>> {
+        let close = self.delimiters.close;
+        let brace = match self.braces {
+            Braces::Two => 0,
+            Braces::Three if self.source.starts_with('}') => 1,
+            Braces::Three => return None,
+        };
+
+        if self.source[brace..].starts_with(close) {
+            Some(Leave(self.advance(brace + close.len())?, layout))
+        } else {
+            None
+        }
+    }
+
+    /// Consume a single key of a dotted name, stopping at any dot, whitespace,
+    /// brace or close delimiter.
+    fn ident(&mut self) -> Option<Token<'a>> {
+        let close = self.delimiters.close;
+        let end = self
+            .source
+            .char_indices()
+            .find(|&(index, c)| {
+                matches!(c, '.' | ' ' | '{' | '}') || self.source[index..].starts_with(close)
+            })
+            .map_or(self.source.len(), |(index, _)| index);
+
+        if end == 0 {
+            None
+        } else {
+            Some(Ident(self.advance(end)?))
+        }
+    }
+
+    /// Parse the new delimiters of a `{{=<% %>=}}` tag, without consuming anything.
+    /// The delimiters can't contain whitespace or `=`.
+    fn set_delimiters(&self, start: usize) -> Result<Delimiters<'a>, Error> {
+        let close = self.delimiters.close;
+        let inner = self
+            .source
+            .match_indices('=')
+            .find(|&(index, _)| self.source[index + 1..].starts_with(close))
+            .map(|(index, _)| &self.source[..index]);
+
+        let mut parts = inner.unwrap_or_default().split_whitespace();
+
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(open), Some(close), None) if !open.contains('=') && !close.contains('=') => {
+                Ok(Delimiters { open, close })
+            },
+
+            _ => Err(Error::ParsingFailed(
+                (start, self.position),
+                format!(
+                    "{open}={inner}={close} is not a valid set delimiter tag",
+                    open = self.delimiters.open,
+                    inner = inner.unwrap_or(self.source),
+                    close = close,
+                ),
+            )),
+        }
+    }
+
     /// Consume a token on match.
     fn token<F>(&mut self, token: &str, action: F) -> Option<Token<'a>>
     where
@@ -351,15 +476,10 @@ impl<'a> Lexer<'a> {
 
     /// Skip whitespace, updating the position.
     fn skip_whitespace(&mut self) {
-        let mut count = 0usize;
+        let trimmed = self.source.trim_start();
 
-        self.source = self.source.trim_start_matches(|c: char| {
-            let ws = c.is_whitespace();
-            count += 1;
-            ws
-        });
-
-        self.position += count;
+        self.position += self.source.len() - trimmed.len();
+        self.source = trimmed;
     }
 
     /// Skip a single newline, updating the position.
@@ -395,7 +515,7 @@ fn print_tokens() {
     // let source = " | {{#boolean}} {{! Important Whitespace }}\n {{/boolean}} | \n";
     let source = "|\r\n{{#boolean}}\r\n{{/boolean}}\r\n|";
     let source = r#""{{person.name}}" == "{{#person}}{{name}}{{/person}}""#;
-    let lexer = Lexer::new(source);
+    let lexer = Lexer::with_delimiters(source, Delimiters::default());
     let tokens = lexer.collect::<Vec<_>>();
 
     println!("{:#?}", tokens);
@@ -2,35 +2,56 @@
 #![warn(clippy::disallowed_types)]
 use std::{
     borrow::Cow,
-    collections::hash_map::Entry,
     ffi::{
         OsStr,
         OsString,
     },
     fs,
+    io,
     path::{
         Path,
         PathBuf,
     },
+    sync::{
+        Arc,
+        PoisonError,
+        RwLock,
+        Weak,
+    },
 };
 
 #[cfg(feature = "derive")]
 pub use pistachio_derive::Render;
 
-// #[cfg(feature = "serde_json")]
-// pub use serde_json::{
-//     json,
-//     Value,
-// };
-//
+#[cfg(any(feature = "chrono", feature = "time"))]
+pub use self::render::Formatted;
+#[cfg(feature = "serde")]
+pub use self::render::Serialized;
 pub use self::{
     error::Error,
     map::Map,
     parser::ParseError,
-    render::Render,
+    render::{
+        Debugged,
+        Displayed,
+        Entries,
+        Iter,
+        IterFn,
+        Lambda,
+        Lazy,
+        NumberFormat,
+        Order,
+        Render,
+        SectionLambda,
+        Truthiness,
+        Value,
+    },
     template::Template,
 };
 
+#[cfg(feature = "conformance")]
+pub mod conformance;
+
 mod error;
 mod lexer;
 mod map;
@@ -55,25 +76,43 @@ pub enum Cache {
 
 #[derive(Debug)]
 pub struct Builder {
-    directory: PathBuf,
+    directory: Option<PathBuf>,
     extension: OsString,
     cache: Cache,
     raise: bool,
+    number_format: NumberFormat,
+    truthiness: Truthiness,
 }
 
 impl Builder {
     pub fn build(self) -> Result<Pistachio, Error> {
-        Ok(Pistachio {
-            directory: self.directory.canonicalize().map_err(Error::Io)?,
+        let directory = self
+            .directory
+            .map(|directory| directory.canonicalize().map_err(Error::Io))
+            .transpose()?;
+        let inner = Arc::new_cyclic(|this| Inner {
+            directory,
             extension: self.extension,
-            templates: map::with_capacity(4),
+            templates: RwLock::new(map::with_capacity(4)),
             cache: self.cache,
             raise: self.raise,
-        })
+            number_format: self.number_format,
+            truthiness: self.truthiness,
+            this: this.clone(),
+        });
+
+        Ok(Pistachio { inner })
     }
 
     pub fn directory<P: AsRef<Path>>(mut self, dir: P) -> Self {
-        self.directory = dir.as_ref().into();
+        self.directory = Some(dir.as_ref().into());
+        self
+    }
+
+    /// Don't load templates from the filesystem. Only templates added via
+    /// [`Pistachio::add`] can be obtained or used as partials and parents.
+    pub fn in_memory(mut self) -> Self {
+        self.directory = None;
         self
     }
 
@@ -87,20 +126,45 @@ impl Builder {
         self
     }
 
+    /// Render missing `{{name}}` variables and `{{#name}}` sections as if they
+    /// were false, rather than raising [`Error::MissingVariable`].
+    ///
+    /// A dotted name such as `a.b.c` is resolved against the first value on the
+    /// context stack containing `a`, and is missing if `b` or `c` are absent.
+    /// Inverted `{{^name}}` sections always treat missing names as false.
+    ///
+    /// This also renders nothing for a `{{>*name}}` or `{{<*name}}` tag whose
+    /// dynamic name is missing, or for any partial or parent that names a
+    /// template which doesn't exist, rather than raising
+    /// [`Error::MissingTemplate`].
     pub fn missing_is_false(mut self) -> Self {
         self.raise = false;
         self
     }
+
+    /// Render numbers with the given decimal places, significant digits and
+    /// separators, rather than their `Display` form.
+    pub fn number_format(mut self, format: NumberFormat) -> Self {
+        self.number_format = format;
+        self
+    }
+
+    /// Decide which values are falsey with the given rules, such as
+    /// [`Truthiness::javascript`] to match `mustache.js`.
+    pub fn truthiness(mut self, truthiness: Truthiness) -> Self {
+        self.truthiness = truthiness;
+        self
+    }
 }
 
 /// Everybody loves `Pistachio`.
-#[derive(Debug)]
+///
+/// Cloning is cheap and shares the same underlying template cache. Templates
+/// obtained from a `Pistachio` resolve their `{{>partial}}` and `{{<parent}}`
+/// tags against it when rendered.
+#[derive(Debug, Clone)]
 pub struct Pistachio {
-    directory: PathBuf,
-    extension: OsString,
-    templates: map::Map<Cow<'static, str>, Template<'static>>,
-    cache: Cache,
-    raise: bool,
+    inner: Arc<Inner>,
 }
 
 impl Pistachio {
@@ -113,110 +177,155 @@ impl Pistachio {
     /// behaviour, see [`Builder::missing_is_false`].
     pub fn builder() -> Builder {
         Builder {
-            directory: "examples".into(),
+            directory: Some("examples".into()),
             extension: "mustache".into(),
             cache: Cache::Name,
             raise: true,
+            number_format: NumberFormat::new(),
+            truthiness: Truthiness::new(),
         }
     }
 
     /// Get an existing template from this `Pistachio`, reading it from the filesystem
     /// and parsing it, if not already present in memory.
-    pub fn get(&mut self, name: &str) -> Result<&Template<'static>, Error> {
-        match self.cache {
-            Cache::Name if self.templates.contains_key(name) => {},
-            Cache::Name => {
-                self.read_template(Cow::Owned(name.to_string()))?;
-            },
-            Cache::None => {
-                self.read_template(Cow::Owned(name.to_string()))?;
-            },
-        }
-
-        Ok(&self.templates[name])
+    pub fn get(&self, name: &str) -> Result<Arc<Template<'static>>, Error> {
+        self.inner.get_template(name)
     }
 
     /// Add a template to this `Pistachio`.
     pub fn add<Name, Source>(
-        &mut self,
+        &self,
         name: Name,
         source: Source,
-    ) -> Result<&Template<'static>, Error>
+    ) -> Result<Arc<Template<'static>>, Error>
     where
         Name: Into<Cow<'static, str>>,
         Source: Into<Cow<'static, str>>,
     {
-        let name = name.into();
-        let template = Template::with_loader(source.into(), self)?;
+        let template = self.inner.parse_template(source.into())?;
 
-        Ok(self.insert_template(name, template))
+        Ok(self.inner.insert_template(name.into(), template))
     }
+}
 
+#[derive(Debug)]
+struct Inner {
+    directory: Option<PathBuf>,
+    extension: OsString,
+    templates: RwLock<map::Map<Cow<'static, str>, Arc<Template<'static>>>>,
+    cache: Cache,
+    raise: bool,
+    number_format: NumberFormat,
+    truthiness: Truthiness,
+    this: Weak<Inner>,
+}
+
+impl Inner {
     #[inline]
-    fn read_template(&mut self, name: Cow<'static, str>) -> Result<&Template<'static>, Error> {
-        let path = self
-            .directory
-            .join(name.as_ref())
+    fn read_template(&self, name: &str) -> Result<Arc<Template<'static>>, Error> {
+        let directory = match &self.directory {
+            Some(directory) => directory,
+            None => return Err(Error::MissingTemplate(name.to_string())),
+        };
+
+        let path = directory
+            .join(name)
             .with_extension(&self.extension)
             .canonicalize()
-            .map_err(Error::Io)?;
+            .map_err(|err| match err.kind() {
+                io::ErrorKind::NotFound => Error::MissingTemplate(name.to_string()),
+                _ => Error::Io(err),
+            })?;
 
-        if !path.starts_with(&self.directory) {
+        if !path.starts_with(directory) {
             return Err(Error::InvalidPartial(path.display().to_string()));
         }
 
         let source = fs::read_to_string(&path).map_err(Error::Io)?;
-        let template = Template::with_loader(source.into(), self)?;
+        let template = self.parse_template(source.into())?;
+
+        Ok(self.insert_template(name.to_string().into(), template))
+    }
+
+    #[inline]
+    fn parse_template(&self, source: Cow<'static, str>) -> Result<Template<'static>, Error> {
+        let loader: Weak<dyn Loader> = self.this.clone();
 
-        Ok(self.insert_template(name, template))
+        Template::with_loader(source, loader)
     }
 
     #[inline]
     fn insert_template(
-        &mut self,
+        &self,
         name: Cow<'static, str>,
         template: Template<'static>,
-    ) -> &Template<'static> {
-        // https://doc.rust-lang.org/std/collections/hash_map/enum.Entry.html#method.insert_entry
-        match self.templates.entry(name) {
-            Entry::Occupied(mut entry) => {
-                entry.insert(template);
-                &*entry.into_mut()
-            },
-            Entry::Vacant(entry) => &*entry.insert(template),
-        }
+    ) -> Arc<Template<'static>> {
+        let template = Arc::new(template);
+
+        self.templates
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(name, template.clone());
+
+        template
     }
 }
 
-pub trait Loader<'a> {
-    /// Invoked as a callback by the LR parser to obtain a child template when
+/// Resolves `{{<parent}}` and `{{>partial}}` names to templates.
+pub trait Loader: Send + Sync {
+    /// Invoked as a callback during rendering to obtain a child template when
     /// `{{<parent}}` or `{{>partial}}` are encountered.
-    fn get_template(&mut self, name: &'a str) -> Result<&Template<'a>, Error>;
+    fn get_template(&self, name: &str) -> Result<Arc<Template<'static>>, Error>;
 
     /// If missing `{{foo}}` variables should raise an error.
     fn raise_if_missing(&self) -> bool {
         false
     }
+
+    /// How numbers are rendered.
+    fn number_format(&self) -> &NumberFormat {
+        &render::DEFAULT_NUMBER_FORMAT
+    }
+
+    /// Which values are falsey.
+    fn truthiness(&self) -> &Truthiness {
+        &render::DEFAULT_TRUTHINESS
+    }
 }
 
 pub struct LoadingDisabled;
 
-impl<'a> Loader<'a> for LoadingDisabled {
-    fn get_template(&mut self, _name: &'a str) -> Result<&Template<'a>, Error> {
+impl Loader for LoadingDisabled {
+    fn get_template(&self, _name: &str) -> Result<Arc<Template<'static>>, Error> {
         Err(Error::LoadingDisabled)
     }
 }
 
-impl Loader<'static> for Pistachio {
-    fn get_template(&mut self, name: &'static str) -> Result<&Template<'static>, Error> {
-        if !self.templates.contains_key(name) {
-            self.read_template(name.into())?;
+impl Loader for Inner {
+    fn get_template(&self, name: &str) -> Result<Arc<Template<'static>>, Error> {
+        // In-memory templates are always cached, since they can't be reloaded.
+        if matches!(self.cache, Cache::Name) || self.directory.is_none() {
+            let templates = self
+                .templates
+                .read()
+                .unwrap_or_else(PoisonError::into_inner);
+            if let Some(template) = templates.get(name) {
+                return Ok(template.clone());
+            }
         }
 
-        Ok(&self.templates[name])
+        self.read_template(name)
     }
 
     fn raise_if_missing(&self) -> bool {
         self.raise
     }
+
+    fn number_format(&self) -> &NumberFormat {
+        &self.number_format
+    }
+
+    fn truthiness(&self) -> &Truthiness {
+        &self.truthiness
+    }
 }
@@ -26,18 +26,18 @@ impl<T: Spanned> Spanned for &T {
 
 impl Spanned for (usize, &str) {
     fn span(&self) -> (usize, usize) {
-        (self.0, self.1.len())
+        (self.0, self.0 + self.1.len())
     }
 }
 
-// XXX: doesn't consider custom delimiters
 macro_rules! balanced {
-    ($token:literal, $open:expr, $close:expr, $span:expr, $action:expr) => {
+    ($token:literal, $delimiters:expr, $open:expr, $close:expr, $span:expr, $action:expr) => {
         if $open == $close {
             $action
         } else {
+            let crate::lexer::Delimiters { open: enter, close: leave } = $delimiters;
             let msg = format!(
-                "{{{{{token}{open}}}}} is missing the corresponding {{{{/{close}}}}} close tag",
+                "{enter}{token}{open}{leave} is missing the corresponding {enter}/{close}{leave} close tag",
                 token = $token,
                 open = $open,
                 close = $close,
@@ -1,8 +1,31 @@
 use std::fmt;
 
+#[cfg(any(feature = "chrono", feature = "time"))]
+pub use self::value::Formatted;
+#[cfg(feature = "serde")]
+pub use self::value::Serialized;
+pub(crate) use self::value::{
+    DEFAULT_NUMBER_FORMAT,
+    DEFAULT_TRUTHINESS,
+};
 pub use self::{
     context::Context,
     stack::Stack,
+    value::{
+        Debugged,
+        Displayed,
+        Entries,
+        Iter,
+        IterFn,
+        Iteration,
+        Lambda,
+        Lazy,
+        NumberFormat,
+        Order,
+        SectionLambda,
+        Truthiness,
+        Value,
+    },
     writer::Writer,
 };
 use crate::{
@@ -22,14 +45,12 @@ pub trait Render: fmt::Debug {
     }
 
     #[inline]
-    fn is_truthy(&self) -> bool {
+    fn is_truthy(&self, _truthiness: &Truthiness) -> bool {
         true
     }
 
     #[inline]
     fn render_escaped(&self, _context: Context, _writer: &mut Writer) -> Result<(), Error> {
-        println!("default:render_escaped {:?}", self);
-
         // XXX: what about erroring by default - this way trying to use
         // something like {{ foo.bar.baz }} where baz is actually a lambda, will error.
         Ok(())
@@ -37,8 +58,6 @@ pub trait Render: fmt::Debug {
 
     #[inline]
     fn render_unescaped(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
-        println!("default:render_unescaped {:?}", self);
-
         // XXX: what about erroring by default - this way trying to use
         // something like {{ foo.bar.baz }} where baz is actually a lambda, will error.
         self.render_escaped(context, writer)
@@ -46,9 +65,7 @@ pub trait Render: fmt::Debug {
 
     #[inline]
     fn render_section(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
-        println!("default:render_section {:?}", self);
-
-        if self.is_truthy() {
+        if self.is_truthy(context.truthiness()) {
             context.render_to_writer(writer)
         } else {
             Ok(())
@@ -57,15 +74,39 @@ pub trait Render: fmt::Debug {
 
     #[inline]
     fn render_inverted(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
-        println!("default:render_inverted {:?}", self);
-
-        if !self.is_truthy() {
+        if !self.is_truthy(context.truthiness()) {
             context.render_to_writer(writer)
         } else {
             Ok(())
         }
     }
 
+    /// The value for `key`, if this is a map or struct containing one.
+    ///
+    /// The `render_named_*` methods resolve each key of a dotted name using
+    /// this by default, pushing each value onto the stack as they descend.
+    /// Named `lookup` rather than `get` so as not to shadow `Vec::get` and
+    /// friends wherever the trait is in scope.
+    #[inline]
+    fn lookup(&self, _key: &str) -> Option<&dyn Render> {
+        None
+    }
+
+    /// The number of items, if this is a sequence, which `length` resolves
+    /// to in names such as `{{items.length}}`.
+    #[inline]
+    fn length(&self) -> Option<usize> {
+        None
+    }
+
+    /// Whether this has a value for `key`, which decides the frame a dotted
+    /// name belongs to. Only types that resolve names without implementing
+    /// [`Render::lookup`] and [`Render::length`] need to override this.
+    #[inline]
+    fn contains_key(&self, key: &str) -> bool {
+        self.lookup(key).is_some() || (key == "length" && self.length().is_some())
+    }
+
     /// XXX: throw missing variable errors by default?
 
     #[inline]
@@ -76,10 +117,17 @@ pub trait Render: fmt::Debug {
         writer: &mut Writer,
     ) -> Result<bool, Error> {
         // If the name is empty the stack is fully prepared.
-        if name.is_empty() {
-            self.render_escaped(context, writer).map(|_| true)
-        } else {
-            Ok(false)
+        match name.split_first() {
+            None => self.render_escaped(context, writer).map(|_| true),
+            Some((key, name)) => match self.lookup(key) {
+                Some(value) => value.render_named_escaped(name, context.push(value), writer),
+                None => match self.length() {
+                    Some(len) if *key == "length" => {
+                        len.render_named_escaped(name, context.push(&len), writer)
+                    },
+                    _ => Ok(false),
+                },
+            },
         }
     }
 
@@ -91,10 +139,17 @@ pub trait Render: fmt::Debug {
         writer: &mut Writer,
     ) -> Result<bool, Error> {
         // If the name is empty the stack is fully prepared.
-        if name.is_empty() {
-            self.render_unescaped(context, writer).map(|_| true)
-        } else {
-            Ok(false)
+        match name.split_first() {
+            None => self.render_unescaped(context, writer).map(|_| true),
+            Some((key, name)) => match self.lookup(key) {
+                Some(value) => value.render_named_unescaped(name, context.push(value), writer),
+                None => match self.length() {
+                    Some(len) if *key == "length" => {
+                        len.render_named_unescaped(name, context.push(&len), writer)
+                    },
+                    _ => Ok(false),
+                },
+            },
         }
     }
 
@@ -106,10 +161,17 @@ pub trait Render: fmt::Debug {
         writer: &mut Writer,
     ) -> Result<bool, Error> {
         // If the name is empty the stack is fully prepared.
-        if name.is_empty() {
-            self.render_section(context, writer).map(|_| true)
-        } else {
-            Ok(false)
+        match name.split_first() {
+            None => self.render_section(context, writer).map(|_| true),
+            Some((key, name)) => match self.lookup(key) {
+                Some(value) => value.render_named_section(name, context.push(value), writer),
+                None => match self.length() {
+                    Some(len) if *key == "length" => {
+                        len.render_named_section(name, context.push(&len), writer)
+                    },
+                    _ => Ok(false),
+                },
+            },
         }
     }
 
@@ -121,10 +183,17 @@ pub trait Render: fmt::Debug {
         writer: &mut Writer,
     ) -> Result<bool, Error> {
         // If the name is empty the stack is fully prepared.
-        if name.is_empty() {
-            self.render_inverted(context, writer).map(|_| true)
-        } else {
-            Ok(false)
+        match name.split_first() {
+            None => self.render_inverted(context, writer).map(|_| true),
+            Some((key, name)) => match self.lookup(key) {
+                Some(value) => value.render_named_inverted(name, context.push(value), writer),
+                None => match self.length() {
+                    Some(len) if *key == "length" => {
+                        len.render_named_inverted(name, context.push(&len), writer)
+                    },
+                    _ => Ok(false),
+                },
+            },
         }
     }
 }
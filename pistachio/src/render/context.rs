@@ -1,48 +1,164 @@
-use std::ops::Range;
+use std::{
+    fmt,
+    sync::Arc,
+};
 
 use super::{
+    NumberFormat,
     Render,
     Stack,
+    Truthiness,
     Writer,
 };
 use crate::{
     error::Error,
+    lexer::Delimiters,
+    parser::Spanned,
     template::{
         Name,
         Node,
         Tag,
         Template,
     },
+    Loader,
+    LoadingDisabled,
 };
 
 /// The mustache context containing the execution stack and current sub-tree of nodes.
-#[derive(Debug, Clone, Copy)]
+#[derive(Clone, Copy)]
 pub struct Context<'a> {
     stack: Stack<'a>,
     nodes: &'a [Node<'a>],
+    /// The innermost `{{#section}}` tag being rendered, if any.
+    section: Option<&'a Node<'a>>,
+    blocks: Option<&'a Blocks<'a>>,
+    loader: &'a dyn Loader,
     raise: bool,
+    number_format: &'a NumberFormat,
+    truthiness: &'a Truthiness,
+}
+
+/// The `{{$block}}` overrides supplied by each enclosing `{{<parent}}` tag,
+/// innermost first.
+#[derive(Debug, Clone, Copy)]
+struct Blocks<'a> {
+    /// The children of the `{{<parent}}` tag.
+    nodes: &'a [Node<'a>],
+    outer: Option<&'a Blocks<'a>>,
+}
+
+impl<'a> Blocks<'a> {
+    /// Find the override for the named block. The outermost override wins,
+    /// so that a template always has the final say over its ancestors.
+    fn get(&self, name: &Name) -> Option<&'a [Node<'a>]> {
+        let outer = self.outer.and_then(|outer| outer.get(name));
+        let mut index = 0;
+
+        while outer.is_none() {
+            let node = self.nodes.get(index)?;
+            let children = node.children() as usize;
+
+            index += 1;
+
+            if node.tag == Tag::Block && node.name == *name {
+                return Some(&self.nodes[index..index + children]);
+            }
+
+            index += children;
+        }
+
+        outer
+    }
+}
+
+impl fmt::Debug for Context<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Context")
+            .field("stack", &self.stack)
+            .field("nodes", &self.nodes)
+            .field("section", &self.section)
+            .field("blocks", &self.blocks)
+            .field("raise", &self.raise)
+            .field("number_format", &self.number_format)
+            .field("truthiness", &self.truthiness)
+            .finish()
+    }
 }
 
 impl<'a> Context<'a> {
-    pub fn new(raise: bool, nodes: &'a [Node<'a>], frame: &'a dyn Render) -> Self {
+    pub fn new(
+        raise: bool,
+        number_format: &'a NumberFormat,
+        truthiness: &'a Truthiness,
+        nodes: &'a [Node<'a>],
+        frame: &'a dyn Render,
+        loader: Option<&'a dyn Loader>,
+    ) -> Self {
         Self {
-            stack: Stack::new().push(frame),
+            stack: Stack::new(frame),
             nodes,
+            section: None,
+            blocks: None,
+            loader: loader.unwrap_or(&LoadingDisabled),
             raise,
+            number_format,
+            truthiness,
         }
     }
 
-    pub fn push(self, frame: &'a dyn Render) -> Self {
-        Self {
+    pub fn push<'b>(&'b self, frame: &'b dyn Render) -> Context<'b> {
+        Context {
             stack: self.stack.push(frame),
-            ..self
+            ..*self
         }
     }
 
-    pub fn peek(&self) -> &dyn Render {
+    /// A copy of this context with the top of the stack replaced by `frame`.
+    pub(crate) fn replace<'b>(&self, frame: &'b dyn Render) -> Context<'b>
+    where
+        'a: 'b,
+    {
+        Context {
+            stack: self.stack.replace(frame),
+            ..*self
+        }
+    }
+
+    /// How numbers are rendered, as configured on the `Pistachio` the
+    /// template came from.
+    #[inline]
+    pub fn number_format(&self) -> &'a NumberFormat {
+        self.number_format
+    }
+
+    /// Which values are falsey, as configured on the `Pistachio` the template
+    /// came from.
+    #[inline]
+    pub fn truthiness(&self) -> &'a Truthiness {
+        self.truthiness
+    }
+
+    pub fn peek(&self) -> &'a dyn Render {
         self.stack.peek()
     }
 
+    /// Resolve a name such as `user.name`, or `.` for the top of the stack,
+    /// to its value. A dotted name belongs to the first frame containing its
+    /// first key, as with `{{name}}` tags.
+    ///
+    /// Only values reachable through [`Render::lookup`] are found, so names
+    /// computed while rendering, such as `items.length` or `@index`, are not.
+    pub fn lookup(&self, name: &str) -> Option<&'a dyn Render> {
+        if name == "." {
+            return Some(self.peek());
+        }
+
+        let mut keys = name.split('.');
+        let value = self.stack.lookup(keys.next()?)?;
+
+        keys.try_fold(value, |value, key| value.lookup(key))
+    }
+
     pub fn render_inline(self, nodes: &'a [Node<'a>], writer: &mut Writer) -> Result<(), Error> {
         Self { nodes, ..self }.render_to_writer(writer)
     }
@@ -65,130 +181,205 @@ impl<'a> Context<'a> {
         while let Some(node) = self.nodes.get(index) {
             index += 1;
 
-            writer.write_unescaped(node.text)?;
-
-            println!("{:?}", self.stack);
+            writer.write_content(node.text)?;
 
             match node.tag {
                 Tag::Escaped => {
                     let found = self.stack.render_named_escaped(&node.name, self, writer)?;
-                    if !found {
-                        // return Err(Error::MissingVariable(node.span(), node.name.into()));
-                    }
+                    self.check_missing(found, node)?;
                 },
 
                 Tag::Unescaped => {
                     let found = self
                         .stack
                         .render_named_unescaped(&node.name, self, writer)?;
-                    if !found {
-                        // return Err(Error::MissingVariable(node.span(), node.name.into()));
-                    }
+                    self.check_missing(found, node)?;
                 },
 
                 Tag::Section => {
                     let children = node.children() as usize;
-                    self.stack.render_named_section(
+                    let found = self.stack.render_named_section(
                         &node.name,
                         Self {
                             nodes: &self.nodes[index..index + children],
+                            section: Some(node),
                             ..self
                         },
                         writer,
                     )?;
+                    self.check_missing(found, node)?;
 
                     index += children;
                 },
 
                 Tag::Inverted => {
                     let children = node.children() as usize;
-                    self.stack.render_named_inverted(
-                        &node.name,
-                        Self {
-                            nodes: &self.nodes[index..index + children],
-                            ..self
-                        },
-                        writer,
-                    )?;
+                    let context = Self {
+                        nodes: &self.nodes[index..index + children],
+                        ..self
+                    };
+
+                    // A name that cannot be found on the stack is falsey, even
+                    // when raising errors, so `{{^name}}` can test for absence.
+                    if !self
+                        .stack
+                        .render_named_inverted(&node.name, context, writer)?
+                    {
+                        context.render_to_writer(writer)?;
+                    }
 
                     index += children;
                 },
 
-                Tag::Block => {},
+                Tag::Block => {
+                    let children = node.children() as usize;
+                    let nodes = self
+                        .blocks
+                        .and_then(|blocks| blocks.get(&node.name))
+                        .unwrap_or(&self.nodes[index..index + children]);
+
+                    Self { nodes, ..self }.render_to_writer(writer)?;
 
-                Tag::Parent => {},
+                    index += children;
+                },
+
+                Tag::Parent => {
+                    let children = node.children() as usize;
+                    let blocks = Blocks {
+                        nodes: &self.nodes[index..index + children],
+                        outer: self.blocks,
+                    };
+
+                    self.render_parent(node, &blocks, writer)?;
+
+                    index += children;
+                },
 
-                Tag::Partial => {},
+                Tag::Partial => {
+                    self.render_partial(node, writer)?;
+                },
 
                 Tag::Closing => {},
 
                 Tag::Content => {},
+            }
+        }
 
-                _ => {
-                    todo!()
-                },
+        Ok(())
+    }
+
+    /// Raise an error for a name that cannot be found on the stack, unless
+    /// missing names are configured to be falsey.
+    #[inline]
+    fn check_missing(&self, found: bool, node: &Node) -> Result<(), Error> {
+        if found || !self.raise {
+            Ok(())
+        } else {
+            Err(Error::MissingVariable(
+                node.name.span(),
+                node.name.to_string(),
+            ))
+        }
+    }
+
+    /// The unparsed content of the innermost section, as passed to section lambdas.
+    pub(crate) fn section_source(&self) -> &'a str {
+        self.section.map_or("", |node| node.raw)
+    }
+
+    /// Render the text returned by a section lambda, using the delimiters in
+    /// effect at the `{{#section}}` tag.
+    pub(crate) fn render_section_source(
+        self,
+        source: &str,
+        writer: &mut Writer,
+    ) -> Result<(), Error> {
+        let delimiters = self.section.map(|node| node.delimiters).unwrap_or_default();
+        let template = Template::parse(source.into(), self.raise, None, delimiters)?;
+
+        Context {
+            nodes: template.nodes(),
+            section: None,
+            ..self
+        }
+        .render_to_writer(writer)
+    }
+
+    /// Render the text returned by an interpolated lambda, which always uses
+    /// the default delimiters.
+    pub(crate) fn render_source_to_string(self, source: &str) -> Result<String, Error> {
+        let template = Template::parse(source.into(), self.raise, None, Delimiters::default())?;
+
+        Context {
+            nodes: template.nodes(),
+            section: None,
+            ..self
+        }
+        .render_to_string(source.len())
+    }
+
+    fn render_partial(self, node: &Node, writer: &mut Writer) -> Result<(), Error> {
+        let template = match self.get_template(node)? {
+            Some(template) => template,
+            None => return Ok(()),
+        };
+
+        writer.indented(node.indent, |writer| {
+            Context {
+                nodes: template.nodes(),
+                ..self
             }
+            .render_to_writer(writer)
+        })
+    }
+
+    fn render_parent(self, node: &Node, blocks: &Blocks, writer: &mut Writer) -> Result<(), Error> {
+        let template = match self.get_template(node)? {
+            Some(template) => template,
+            None => return Ok(()),
+        };
+
+        Context {
+            nodes: template.nodes(),
+            blocks: Some(blocks),
+            ..self
         }
+        .render_to_writer(writer)
+    }
 
-        println!("exit render");
+    /// The template included by a partial or parent tag, or `None` if nothing
+    /// should be rendered.
+    ///
+    /// A dynamic name such as `{{>*name}}` that can't be found on the stack
+    /// renders nothing, unless raising errors where it's a missing variable.
+    /// Likewise a name that doesn't refer to a template renders nothing, unless
+    /// raising errors where it's [`Error::MissingTemplate`].
+    fn get_template(&self, node: &Node) -> Result<Option<Arc<Template<'static>>>, Error> {
+        let mut buffer = Vec::new();
+        let name = if node.dynamic {
+            // The template name is the value the dynamic name refers to.
+            let mut writer = Writer::new(&mut buffer);
+            let found = self
+                .stack
+                .render_named_unescaped(&node.name, *self, &mut writer)?;
+            if !found {
+                self.check_missing(found, node)?;
+                return Ok(None);
+            }
 
-        Ok(())
+            // A custom `Render` impl may have written anything.
+            std::str::from_utf8(&buffer).map_err(|_| {
+                Error::InvalidPartial(format!("{} is not a valid UTF-8 name", node.name))
+            })?
+        } else {
+            node.name.keys.first().copied().unwrap_or_default()
+        };
+
+        match self.loader.get_template(name) {
+            Ok(template) => Ok(Some(template)),
+            // A partial or parent that cannot be found renders as an empty string.
+            Err(Error::MissingTemplate(_) | Error::LoadingDisabled) if !self.raise => Ok(None),
+            Err(err) => Err(err),
+        }
     }
 }
-
-// pub fn trace(&mut self, trace: &'a str) -> Result<(), Error> {}
-
-// pub fn push<F>(&mut self, frame: &'a dyn Render, action: F) -> Result<(), Error>
-// where
-//     F: FnOnce(&mut Self) -> Result<(), Error>,
-// {
-//     self.stack.push(frame);
-//     let result = action(self);
-//     self.stack.pop();
-
-//     result
-// }
-
-// impl<'a> Context<'a, ()> {
-//     pub fn new(raise: bool, nodes: &'a [Node<'a>]) -> Self {
-//         Self {
-//             raise,
-//             stack: (),
-//             nodes,
-//         }
-//     }
-// }
-
-// impl<'a, S> Context<'a, S>
-// where
-//     S: RenderStack,
-// {
-//     #[inline]
-//     pub fn push<X>(self, frame: &X) -> Context<'a, PushStack<S, &X>>
-//     where
-//         X: ?Sized + Render,
-//     {
-//         Context {
-//             raise: self.raise,
-//             stack: self.stack.push(frame),
-//             nodes: self.nodes,
-//         }
-//     }
-
-//     #[inline]
-//     pub fn pop(self) -> Context<'a, S::Previous> {
-//         Context {
-//             raise: self.raise,
-//             stack: self.stack.pop(),
-//             nodes: self.nodes,
-//         }
-//     }
-
-//     #[inline]
-//     fn children(self, range: Range<usize>) -> Self {
-//         Context {
-//             raise: self.raise,
-//             stack: self.stack,
-//             nodes: &self.nodes[range],
-//         }
-//     }
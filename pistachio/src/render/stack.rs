@@ -1,4 +1,4 @@
-use std::fmt;
+use std::iter;
 
 use super::{
     Context,
@@ -10,90 +10,72 @@ use crate::{
     template::Name,
 };
 
+/// The context stack, as a linked list of frames borrowed from the callers
+/// further up the (Rust) call stack. Pushing a frame is therefore a matter of
+/// pointing at the current stack, which allows for arbitrary nesting.
 #[derive(Debug, Clone, Copy)]
 pub struct Stack<'a> {
-    a: &'a (dyn Render),
-    b: &'a (dyn Render),
-    c: &'a (dyn Render),
-    d: &'a (dyn Render),
-    e: &'a (dyn Render),
-    f: &'a (dyn Render),
+    frame: &'a dyn Render,
+    parent: Option<&'a Stack<'a>>,
 }
 
-// impl fmt::Debug for Stack<'_> {
-//     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-//         write!(f, "Stack")
-//     }
-// }
-
 impl<'a> Stack<'a> {
     #[inline]
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(frame: &'a dyn Render) -> Self {
         Self {
-            a: &(),
-            b: &(),
-            c: &(),
-            d: &(),
-            e: &(),
-            f: &(),
+            frame,
+            parent: None,
         }
     }
 
     #[inline]
-    pub fn push(self, frame: &'a (dyn Render)) -> Self {
-        Self {
-            a: frame,
-            b: self.a,
-            c: self.b,
-            d: self.c,
-            e: self.d,
-            f: self.e,
+    pub fn push<'b>(&'b self, frame: &'b dyn Render) -> Stack<'b> {
+        Stack {
+            frame,
+            parent: Some(self),
+        }
+    }
+
+    /// This stack with its top frame replaced, such as by the value a lock
+    /// guards in place of the lock.
+    #[inline]
+    pub(crate) fn replace<'b>(&self, frame: &'b dyn Render) -> Stack<'b>
+    where
+        'a: 'b,
+    {
+        Stack {
+            frame,
+            parent: self.parent,
         }
     }
 
-    // #[inline]
-    // pub fn pop(self) -> Self {
-    //     Self {
-    //         a: self.b,
-    //         b: &(),
-    //         // c: self.d,
-    //         // d: self.e,
-    //         // e: self.f,
-    //         // f: &(),
-    //     }
-    // }
+    #[inline]
+    pub fn peek(&self) -> &'a dyn Render {
+        self.frame
+    }
 
+    /// The value of the first frame containing `key`, from the top of the
+    /// stack down.
     #[inline]
-    pub fn peek(&self) -> &dyn Render {
-        self.a
+    pub fn lookup(&self, key: &str) -> Option<&'a dyn Render> {
+        let mut stack = Some(self);
+
+        while let Some(&Stack { frame, parent }) = stack {
+            if let Some(value) = frame.lookup(key) {
+                return Some(value);
+            }
+
+            stack = parent;
+        }
+
+        None
     }
 
-    // pub fn resolve_name(self, name: &Name) -> Option<Self> {
-    //     let name = name.keys;
-    //     if name.is_empty() {
-    //         Some(self)
-    //     // } else if name[0] == "." {
-    //     //     Some(context)
-    //     } else {
-    //         let head = name[0];
-
-    //         if let Some(stack) = self.a.push_key(head, self) {
-    //             stack.push_name(&name[1..])
-    //         } else if let Some(stack) = self.b.push_key(head, self) {
-    //             stack.push_name(&name[1..])
-    //         } else {
-    //             None
-    //         }
-    //     }
-    // }
-
-    // // fn push_name(self, name: &[&str]) -> Option<Self> {
-    // //     if name.is_empty() {
-    // //         Some(self)
-    // //     } else {
-    // //         self.peek().push_key(name[0], self)?.push_name(&name[1..])
-    // //     }
-    // // }
+    /// Iterate over the frames, from the top of the stack down.
+    #[inline]
+    fn frames(&self) -> impl Iterator<Item = &dyn Render> {
+        iter::successors(Some(self), |stack| stack.parent).map(|stack| stack.frame)
+    }
 
     #[inline]
     pub fn render_named_escaped(
@@ -106,19 +88,9 @@ impl<'a> Stack<'a> {
             return self.peek().render_escaped(context, writer).map(|_| true);
         }
 
-        let name = &name.keys;
-        if name.is_empty() {
-            return Ok(false);
-        }
-
-        println!("stack:render_named_escaped {:?}", name);
-
-        Ok(self.a.render_named_escaped(name, context, writer)?
-            || self.b.render_named_escaped(name, context, writer)?
-            || self.c.render_named_escaped(name, context, writer)?
-            || self.d.render_named_escaped(name, context, writer)?
-            || self.e.render_named_escaped(name, context, writer)?
-            || self.f.render_named_escaped(name, context, writer)?)
+        self.render_named(name, context, writer, |frame, name, context, writer| {
+            frame.render_named_escaped(name, context, writer)
+        })
     }
 
     #[inline]
@@ -132,17 +104,9 @@ impl<'a> Stack<'a> {
             return self.peek().render_unescaped(context, writer).map(|_| true);
         }
 
-        let name = &name.keys;
-        if name.is_empty() {
-            return Ok(false);
-        }
-
-        Ok(self.a.render_named_unescaped(name, context, writer)?
-            || self.b.render_named_unescaped(name, context, writer)?
-            || self.c.render_named_unescaped(name, context, writer)?
-            || self.d.render_named_unescaped(name, context, writer)?
-            || self.e.render_named_unescaped(name, context, writer)?
-            || self.f.render_named_unescaped(name, context, writer)?)
+        self.render_named(name, context, writer, |frame, name, context, writer| {
+            frame.render_named_unescaped(name, context, writer)
+        })
     }
 
     #[inline]
@@ -156,17 +120,9 @@ impl<'a> Stack<'a> {
             return self.peek().render_section(context, writer).map(|_| true);
         }
 
-        let name = &name.keys;
-        if name.is_empty() {
-            return Ok(false);
-        }
-
-        Ok(self.a.render_named_section(name, context, writer)?
-            || self.b.render_named_section(name, context, writer)?
-            || self.c.render_named_section(name, context, writer)?
-            || self.d.render_named_section(name, context, writer)?
-            || self.e.render_named_section(name, context, writer)?
-            || self.f.render_named_section(name, context, writer)?)
+        self.render_named(name, context, writer, |frame, name, context, writer| {
+            frame.render_named_section(name, context, writer)
+        })
     }
 
     #[inline]
@@ -180,76 +136,42 @@ impl<'a> Stack<'a> {
             return self.peek().render_inverted(context, writer).map(|_| true);
         }
 
+        self.render_named(name, context, writer, |frame, name, context, writer| {
+            frame.render_named_inverted(name, context, writer)
+        })
+    }
+
+    /// Render the first frame that resolves the name, returning whether any did.
+    ///
+    /// A dotted name such as `a.b.c` belongs to the first frame containing `a`.
+    /// If `b` or `c` are then absent the name is missing, rather than being
+    /// looked up further down the stack.
+    #[inline]
+    fn render_named<F>(
+        &self,
+        name: &Name,
+        context: Context,
+        writer: &mut Writer,
+        render: F,
+    ) -> Result<bool, Error>
+    where
+        F: Fn(&dyn Render, &[&str], Context, &mut Writer) -> Result<bool, Error>,
+    {
         let name = &name.keys;
         if name.is_empty() {
             return Ok(false);
         }
 
-        if !self.a.render_named_section(name, context, writer)?
-            && !self.b.render_named_section(name, context, writer)?
-            && !self.c.render_named_section(name, context, writer)?
-            && !self.d.render_named_section(name, context, writer)?
-        {
-            // None of the above succeeded.
-            ().render_inverted(context, writer).map(|_| true)
-        } else {
-            // One of the predicates succeeded.
-            Ok(true)
+        for frame in self.frames() {
+            if render(frame, name, context, writer)? {
+                return Ok(true);
+            }
+
+            if name.len() > 1 && frame.contains_key(name[0]) {
+                return Ok(false);
+            }
         }
-    }
 
-    // #[inline]
-    // pub fn render_field_section(
-    //     &self,
-    //     name: Name<'a>,
-    //     context: Context,
-    //     writer: &mut Writer,
-    // ) -> Result<(), Error> {
-    //     if name.is_dot() {
-    //         self.a.render_section(context, writer)?;
-    //     } else {
-    //         if !self.a.render_field_section(name, context, writer)? {
-    //             let context = context.pop();
-    //             if !self.b.render_field_section(name, context, writer)? {
-    //                 let context = context.pop();
-    //                 if !self.c.render_field_section(name, context, writer)? {
-    //                     let context = context.pop();
-    //                     if !self.d.render_field_section(name, context, writer)? {
-    //                         let context = context.pop();
-    //                         if !self.e.render_field_section(name, context, writer)? {
-    //                             let context = context.pop();
-    //                             self.f.render_field_section(name, context, writer)?;
-    //                         }
-    //                     }
-    //                 }
-    //             }
-    //         }
-    //     }
-
-    //     Ok(())
-    // }
-
-    // #[inline]
-    // pub fn render_field_inverted(
-    //     &self,
-    //     key: &str,
-    //     context: Context,
-    //     writer: &mut Writer,
-    // ) -> Result<(), Error> {
-    //     if key == Key::DOT {
-    //         self.a.render_inverted(context, writer)?;
-    //     } else {
-    //         if !self.a.render_field_inverted(key, context, writer)?
-    //             && !self.b.render_field_inverted(key, context, writer)?
-    //             && !self.c.render_field_inverted(key, context, writer)?
-    //             && !self.d.render_field_inverted(key, context, writer)?
-    //             && !self.e.render_field_inverted(key, context, writer)?
-    //             && !self.f.render_field_inverted(key, context, writer)?
-    //         {
-    //             context.render_to_writer(writer)?;
-    //         }
-    //     }
-
-    //     Ok(())
-    // }
+        Ok(false)
+    }
 }
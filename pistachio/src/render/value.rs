@@ -1,6 +1,43 @@
 // pub use self::source::Source;
+#[cfg(any(feature = "chrono", feature = "time"))]
+pub use self::formatted::Formatted;
+#[cfg(feature = "serde")]
+pub use self::serialize::Serialized;
+pub use self::{
+    display::{
+        Debugged,
+        Displayed,
+    },
+    dynamic::Value,
+    entries::{
+        Entries,
+        Order,
+    },
+    iter::{
+        Iter,
+        IterFn,
+    },
+    iteration::Iteration,
+    lambda::{
+        Lambda,
+        SectionLambda,
+    },
+    lazy::Lazy,
+    number::NumberFormat,
+    truthiness::Truthiness,
+};
+pub(crate) use self::{
+    number::DEFAULT_NUMBER_FORMAT,
+    truthiness::DEFAULT_TRUTHINESS,
+};
 
+mod display;
+mod dynamic;
+mod entries;
+mod iter;
+mod iteration;
 mod lambda;
+mod lazy;
 mod literal;
 mod map;
 mod number;
@@ -8,6 +45,25 @@ mod pointer;
 mod sequence;
 mod source;
 mod sum;
+mod truthiness;
 
+#[cfg(feature = "chrono")]
+mod chrono;
+#[cfg(feature = "rust_decimal")]
+mod decimal;
+#[cfg(any(feature = "chrono", feature = "time"))]
+mod formatted;
 #[cfg(feature = "serde_json")]
 mod json;
+#[cfg(feature = "serde")]
+mod serialize;
+#[cfg(feature = "time")]
+mod time;
+#[cfg(feature = "toml")]
+mod toml;
+#[cfg(feature = "url")]
+mod url;
+#[cfg(feature = "uuid")]
+mod uuid;
+#[cfg(feature = "serde_yaml")]
+mod yaml;
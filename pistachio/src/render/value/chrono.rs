@@ -0,0 +1,100 @@
+use std::fmt;
+
+use chrono::{
+    format::{
+        Item,
+        StrftimeItems,
+    },
+    DateTime,
+    NaiveDate,
+    NaiveDateTime,
+    NaiveTime,
+    TimeZone,
+};
+
+use super::formatted::Formatted;
+use crate::{
+    error::Error,
+    render::{
+        Context,
+        Render,
+        Writer,
+    },
+    Template,
+};
+
+/// Parse a `strftime` pattern up front, since chrono only reports an invalid
+/// pattern as an opaque `fmt::Error` when formatting.
+fn strftime(format: &str) -> Result<StrftimeItems<'_>, Error> {
+    let items = StrftimeItems::new(format);
+
+    if items.clone().any(|item| item == Item::Error) {
+        Err(Error::InvalidFormat(format.to_string()))
+    } else {
+        Ok(items)
+    }
+}
+
+impl<Tz: TimeZone> Render for DateTime<Tz>
+where
+    Tz::Offset: fmt::Display,
+{
+    #[inline]
+    fn size_hint(&self, _template: &Template) -> usize {
+        25
+    }
+
+    #[inline]
+    fn render_escaped(&self, _context: Context, writer: &mut Writer) -> Result<(), Error> {
+        writer.write_escaped(&self.to_rfc3339())
+    }
+}
+
+impl<Tz: TimeZone> Render for Formatted<DateTime<Tz>, &str>
+where
+    Tz::Offset: fmt::Display,
+{
+    #[inline]
+    fn render_escaped(&self, _context: Context, writer: &mut Writer) -> Result<(), Error> {
+        writer.format_escaped(self.0.format_with_items(strftime(self.1)?))
+    }
+}
+
+macro_rules! impl_naive {
+    ( $($ty:ty : $format:literal),* ) => {
+        $(
+            impl Render for $ty {
+                #[inline]
+                fn size_hint(&self, _template: &Template) -> usize {
+                    $format.len() + 4
+                }
+
+                #[inline]
+                fn render_escaped(
+                    &self,
+                    _context: Context,
+                    writer: &mut Writer,
+                ) -> Result<(), Error> {
+                    writer.format_escaped(self.format($format))
+                }
+            }
+
+            impl Render for Formatted<$ty, &str> {
+                #[inline]
+                fn render_escaped(
+                    &self,
+                    _context: Context,
+                    writer: &mut Writer,
+                ) -> Result<(), Error> {
+                    writer.format_escaped(self.0.format_with_items(strftime(self.1)?))
+                }
+            }
+        )*
+    };
+}
+
+impl_naive!(
+    NaiveDate: "%Y-%m-%d",
+    NaiveDateTime: "%Y-%m-%dT%H:%M:%S%.f",
+    NaiveTime: "%H:%M:%S%.f"
+);
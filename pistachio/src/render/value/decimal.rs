@@ -0,0 +1,31 @@
+use rust_decimal::Decimal;
+
+use crate::{
+    error::Error,
+    render::{
+        Context,
+        Render,
+        Truthiness,
+        Writer,
+    },
+    Template,
+};
+
+// Decimals render exactly, with their scale, so `Decimal::round_dp` can be
+// used to pick the number of decimal places.
+impl Render for Decimal {
+    #[inline]
+    fn size_hint(&self, _template: &Template) -> usize {
+        8
+    }
+
+    #[inline]
+    fn is_truthy(&self, truthiness: &Truthiness) -> bool {
+        truthiness.number(self.is_zero())
+    }
+
+    #[inline]
+    fn render_escaped(&self, _context: Context, writer: &mut Writer) -> Result<(), Error> {
+        writer.format_unescaped(self)
+    }
+}
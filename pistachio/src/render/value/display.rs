@@ -0,0 +1,79 @@
+use std::fmt;
+
+use crate::{
+    error::Error,
+    render::{
+        Context,
+        Render,
+        Writer,
+    },
+};
+
+/// Renders a value using its `Display` implementation, escaping the output of
+/// `{{name}}` tags.
+///
+/// Sections treat the value as truthy, since that cannot be known without
+/// formatting it.
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct Displayed<T: ?Sized>(pub T);
+
+/// Renders a value using its `Debug` implementation, escaping the output of
+/// `{{name}}` tags.
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct Debugged<T: ?Sized>(pub T);
+
+impl<T: ?Sized> Displayed<T> {
+    /// Borrow a value as `Displayed`, such as a struct field.
+    pub fn from_ref(value: &T) -> &Self {
+        // Safety: `Displayed` is a transparent wrapper around `T`.
+        unsafe { &*(value as *const T as *const Self) }
+    }
+}
+
+impl<T: ?Sized> Debugged<T> {
+    /// Borrow a value as `Debugged`, such as a struct field.
+    pub fn from_ref(value: &T) -> &Self {
+        // Safety: `Debugged` is a transparent wrapper around `T`.
+        unsafe { &*(value as *const T as *const Self) }
+    }
+}
+
+impl<T: fmt::Display + ?Sized> fmt::Debug for Displayed<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Displayed")
+            .field(&format_args!("{}", &self.0))
+            .finish()
+    }
+}
+
+impl<T: fmt::Debug + ?Sized> fmt::Debug for Debugged<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Debugged").field(&&self.0).finish()
+    }
+}
+
+impl<T: fmt::Display + ?Sized> Render for Displayed<T> {
+    #[inline]
+    fn render_escaped(&self, _context: Context, writer: &mut Writer) -> Result<(), Error> {
+        writer.format_escaped(&self.0)
+    }
+
+    #[inline]
+    fn render_unescaped(&self, _context: Context, writer: &mut Writer) -> Result<(), Error> {
+        writer.format_unescaped(&self.0)
+    }
+}
+
+impl<T: fmt::Debug + ?Sized> Render for Debugged<T> {
+    #[inline]
+    fn render_escaped(&self, _context: Context, writer: &mut Writer) -> Result<(), Error> {
+        writer.format_escaped(format_args!("{:?}", &self.0))
+    }
+
+    #[inline]
+    fn render_unescaped(&self, _context: Context, writer: &mut Writer) -> Result<(), Error> {
+        writer.format_unescaped(format_args!("{:?}", &self.0))
+    }
+}
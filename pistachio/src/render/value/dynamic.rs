@@ -0,0 +1,410 @@
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    fmt,
+    hash::BuildHasher,
+    sync::Arc,
+};
+
+use super::{
+    Lambda,
+    SectionLambda,
+};
+use crate::{
+    error::Error,
+    render::{
+        Context,
+        Render,
+        Truthiness,
+        Writer,
+    },
+    Template,
+};
+
+/// A dynamically typed value, for building template variables at runtime
+/// without declaring a struct. See the [`vars!`](crate::vars) macro.
+#[derive(Clone, Default)]
+pub enum Value {
+    #[default]
+    Null,
+    Bool(bool),
+    Integer(i64),
+    Unsigned(u64),
+    Float(f64),
+    String(String),
+    /// Markup that is never escaped, even when interpolated by `{{name}}`.
+    Raw(String),
+    Array(Vec<Value>),
+    Map(BTreeMap<String, Value>),
+    /// A closure invoked every time it's interpolated, as per [`Lambda`].
+    Lambda(Arc<dyn Fn() -> String + Send + Sync>),
+    /// A closure invoked with the unrendered section content, as per
+    /// [`SectionLambda`].
+    SectionLambda(Arc<dyn Fn(&str) -> String + Send + Sync>),
+}
+
+impl Value {
+    /// Markup that is rendered as-is, without HTML escaping.
+    pub fn raw<S: Into<String>>(html: S) -> Self {
+        Value::Raw(html.into())
+    }
+
+    /// A closure that's invoked every time it's interpolated by `{{name}}`.
+    pub fn lambda<F, S>(f: F) -> Self
+    where
+        F: Fn() -> S + Send + Sync + 'static,
+        S: Into<String>,
+    {
+        Value::Lambda(Arc::new(move || f().into()))
+    }
+
+    /// A closure that's invoked with the unrendered content of a
+    /// `{{#name}}..{{/name}}` section.
+    pub fn section_lambda<F, S>(f: F) -> Self
+    where
+        F: Fn(&str) -> S + Send + Sync + 'static,
+        S: Into<String>,
+    {
+        Value::SectionLambda(Arc::new(move |source| f(source).into()))
+    }
+
+    /// Apply `f` to the value as a [`Render`].
+    #[inline]
+    fn with<R>(&self, f: impl FnOnce(&dyn Render) -> R) -> R {
+        match self {
+            Value::Null => f(&()),
+            Value::Bool(b) => f(b),
+            Value::Integer(n) => f(n),
+            Value::Unsigned(n) => f(n),
+            Value::Float(n) => f(n),
+            Value::String(s) => f(s),
+            Value::Raw(s) => f(&Raw(s)),
+            Value::Array(v) => f(v),
+            Value::Map(m) => f(m),
+            Value::Lambda(l) => f(&Lambda(&**l)),
+            Value::SectionLambda(l) => f(&SectionLambda(&**l)),
+        }
+    }
+}
+
+impl fmt::Debug for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => f.write_str("Null"),
+            Value::Bool(b) => f.debug_tuple("Bool").field(b).finish(),
+            Value::Integer(n) => f.debug_tuple("Integer").field(n).finish(),
+            Value::Unsigned(n) => f.debug_tuple("Unsigned").field(n).finish(),
+            Value::Float(n) => f.debug_tuple("Float").field(n).finish(),
+            Value::String(s) => f.debug_tuple("String").field(s).finish(),
+            Value::Raw(s) => f.debug_tuple("Raw").field(s).finish(),
+            Value::Array(v) => f.debug_tuple("Array").field(v).finish(),
+            Value::Map(m) => f.debug_tuple("Map").field(m).finish(),
+            Value::Lambda(_) => f.write_str("Lambda"),
+            Value::SectionLambda(_) => f.write_str("SectionLambda"),
+        }
+    }
+}
+
+impl Render for Value {
+    #[inline]
+    fn size_hint(&self, template: &Template) -> usize {
+        self.with(|value| value.size_hint(template))
+    }
+
+    #[inline]
+    fn is_truthy(&self, truthiness: &Truthiness) -> bool {
+        self.with(|value| value.is_truthy(truthiness))
+    }
+
+    #[inline]
+    fn lookup(&self, key: &str) -> Option<&dyn Render> {
+        match self {
+            Value::Array(v) => v.lookup(key),
+            Value::Map(m) => m.lookup(key),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn length(&self) -> Option<usize> {
+        self.with(|value| value.length())
+    }
+
+    #[inline]
+    fn contains_key(&self, key: &str) -> bool {
+        self.with(|value| value.contains_key(key))
+    }
+
+    #[inline]
+    fn render_escaped(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        self.with(|value| value.render_escaped(context, writer))
+    }
+
+    #[inline]
+    fn render_unescaped(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        self.with(|value| value.render_unescaped(context, writer))
+    }
+
+    #[inline]
+    fn render_section(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        self.with(|value| value.render_section(context, writer))
+    }
+
+    #[inline]
+    fn render_inverted(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        self.with(|value| value.render_inverted(context, writer))
+    }
+
+    #[inline]
+    fn render_named_escaped(
+        &self,
+        name: &[&str],
+        context: Context,
+        writer: &mut Writer,
+    ) -> Result<bool, Error> {
+        self.with(|value| value.render_named_escaped(name, context, writer))
+    }
+
+    #[inline]
+    fn render_named_unescaped(
+        &self,
+        name: &[&str],
+        context: Context,
+        writer: &mut Writer,
+    ) -> Result<bool, Error> {
+        self.with(|value| value.render_named_unescaped(name, context, writer))
+    }
+
+    #[inline]
+    fn render_named_section(
+        &self,
+        name: &[&str],
+        context: Context,
+        writer: &mut Writer,
+    ) -> Result<bool, Error> {
+        self.with(|value| value.render_named_section(name, context, writer))
+    }
+
+    #[inline]
+    fn render_named_inverted(
+        &self,
+        name: &[&str],
+        context: Context,
+        writer: &mut Writer,
+    ) -> Result<bool, Error> {
+        self.with(|value| value.render_named_inverted(name, context, writer))
+    }
+}
+
+/// Markup that ignores escaping.
+#[derive(Debug)]
+struct Raw<'a>(&'a str);
+
+impl Render for Raw<'_> {
+    #[inline]
+    fn size_hint(&self, _template: &Template) -> usize {
+        self.0.len()
+    }
+
+    #[inline]
+    fn is_truthy(&self, truthiness: &Truthiness) -> bool {
+        truthiness.string(self.0)
+    }
+
+    #[inline]
+    fn render_escaped(&self, _context: Context, writer: &mut Writer) -> Result<(), Error> {
+        writer.write_unescaped(self.0)
+    }
+
+    #[inline]
+    fn render_unescaped(&self, _context: Context, writer: &mut Writer) -> Result<(), Error> {
+        writer.write_unescaped(self.0)
+    }
+}
+
+macro_rules! impl_from {
+    ($variant:ident, $as:ty, $($from:ty)+) => {
+        $(
+            impl From<$from> for Value {
+                #[inline]
+                fn from(value: $from) -> Self {
+                    Value::$variant(value as $as)
+                }
+            }
+        )+
+    };
+}
+
+impl_from!(Integer, i64, i8 i16 i32 i64 isize);
+impl_from!(Unsigned, u64, u8 u16 u32 u64 usize);
+impl_from!(Float, f64, f32 f64);
+
+impl From<bool> for Value {
+    #[inline]
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl From<char> for Value {
+    #[inline]
+    fn from(value: char) -> Self {
+        Value::String(value.to_string())
+    }
+}
+
+impl From<&str> for Value {
+    #[inline]
+    fn from(value: &str) -> Self {
+        Value::String(value.to_string())
+    }
+}
+
+impl From<String> for Value {
+    #[inline]
+    fn from(value: String) -> Self {
+        Value::String(value)
+    }
+}
+
+impl From<Cow<'_, str>> for Value {
+    #[inline]
+    fn from(value: Cow<'_, str>) -> Self {
+        Value::String(value.into_owned())
+    }
+}
+
+impl From<()> for Value {
+    #[inline]
+    fn from(_value: ()) -> Self {
+        Value::Null
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    #[inline]
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Value::Null, Into::into)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    #[inline]
+    fn from(value: Vec<T>) -> Self {
+        Value::Array(value.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T: Clone + Into<Value>> From<&[T]> for Value {
+    #[inline]
+    fn from(value: &[T]) -> Self {
+        Value::Array(value.iter().cloned().map(Into::into).collect())
+    }
+}
+
+impl<T: Into<Value>, const N: usize> From<[T; N]> for Value {
+    #[inline]
+    fn from(value: [T; N]) -> Self {
+        Value::Array(value.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<K: Into<String>, V: Into<Value>> From<BTreeMap<K, V>> for Value {
+    #[inline]
+    fn from(value: BTreeMap<K, V>) -> Self {
+        Value::Map(
+            value
+                .into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        )
+    }
+}
+
+#[allow(clippy::disallowed_types)]
+impl<K, V, H> From<std::collections::HashMap<K, V, H>> for Value
+where
+    K: Into<String>,
+    V: Into<Value>,
+    H: BuildHasher,
+{
+    #[inline]
+    fn from(value: std::collections::HashMap<K, V, H>) -> Self {
+        Value::Map(
+            value
+                .into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        )
+    }
+}
+
+impl<T: Into<Value>> FromIterator<T> for Value {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Value::Array(iter.into_iter().map(Into::into).collect())
+    }
+}
+
+/// Build a [`Value::Map`] from `key: value` pairs.
+///
+/// Keys are identifiers or string literals. Values are nested `{ .. }` maps,
+/// `[ .. ]` arrays, or any expression convertible into a [`Value`].
+#[macro_export]
+macro_rules! vars {
+    ( $($tt:tt)* ) => {{
+        #[allow(unused_mut)]
+        let mut map = ::std::collections::BTreeMap::<::std::string::String, $crate::Value>::new();
+        $crate::__vars_map!(map; $($tt)*);
+        $crate::Value::Map(map)
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __vars_map {
+    ( $map:ident; ) => {};
+
+    ( $map:ident; $key:ident : $($rest:tt)* ) => {
+        $crate::__vars_map!(@value $map; ::std::stringify!($key); $($rest)*);
+    };
+
+    ( $map:ident; $key:literal : $($rest:tt)* ) => {
+        $crate::__vars_map!(@value $map; $key; $($rest)*);
+    };
+
+    ( @value $map:ident; $key:expr; { $($value:tt)* } $(, $($rest:tt)*)? ) => {
+        $map.insert($key.into(), $crate::vars!($($value)*));
+        $crate::__vars_map!($map; $($($rest)*)?);
+    };
+
+    ( @value $map:ident; $key:expr; [ $($value:tt)* ] $(, $($rest:tt)*)? ) => {
+        $map.insert($key.into(), $crate::__vars_array!([] $($value)*));
+        $crate::__vars_map!($map; $($($rest)*)?);
+    };
+
+    ( @value $map:ident; $key:expr; $value:expr $(, $($rest:tt)*)? ) => {
+        $map.insert($key.into(), $crate::Value::from($value));
+        $crate::__vars_map!($map; $($($rest)*)?);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __vars_array {
+    ( [ $($done:expr,)* ] ) => {
+        $crate::Value::Array(::std::vec![$($done),*])
+    };
+
+    ( [ $($done:expr,)* ] { $($value:tt)* } $(, $($rest:tt)*)? ) => {
+        $crate::__vars_array!([ $($done,)* $crate::vars!($($value)*), ] $($($rest)*)?)
+    };
+
+    ( [ $($done:expr,)* ] [ $($value:tt)* ] $(, $($rest:tt)*)? ) => {
+        $crate::__vars_array!([ $($done,)* $crate::__vars_array!([] $($value)*), ] $($($rest)*)?)
+    };
+
+    ( [ $($done:expr,)* ] $value:expr $(, $($rest:tt)*)? ) => {
+        $crate::__vars_array!([ $($done,)* $crate::Value::from($value), ] $($($rest)*)?)
+    };
+}
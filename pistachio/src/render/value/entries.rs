@@ -0,0 +1,121 @@
+use std::{
+    borrow::Borrow,
+    fmt,
+};
+
+use crate::{
+    error::Error,
+    render::{
+        Context,
+        Iteration,
+        Render,
+        Truthiness,
+        Writer,
+    },
+};
+
+/// The entries of a map, rendered as a sequence in sections. Each entry
+/// resolves `key` and `value`, along with the `@index` metadata of sequences.
+///
+/// Entries are sorted by key unless [`Order::Natural`] is requested, since the
+/// iteration order of a `HashMap` differs between runs.
+pub struct Entries<'a, K, V> {
+    entries: Vec<(&'a K, &'a V)>,
+}
+
+/// How the entries of a map are ordered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    /// The map's own iteration order, which is insertion order for `IndexMap`
+    /// and `serde_json::Map` with `preserve_order`.
+    Natural,
+
+    /// Sorted by key.
+    Sorted,
+}
+
+impl<'a, K, V> Entries<'a, K, V>
+where
+    K: Borrow<str>,
+{
+    /// The entries of `map`, sorted by key.
+    pub fn new<M>(map: &'a M) -> Self
+    where
+        M: ?Sized,
+        &'a M: IntoIterator<Item = (&'a K, &'a V)>,
+    {
+        Self::with_order(map, Order::Sorted)
+    }
+
+    /// The entries of `map` in the given order.
+    pub fn with_order<M>(map: &'a M, order: Order) -> Self
+    where
+        M: ?Sized,
+        &'a M: IntoIterator<Item = (&'a K, &'a V)>,
+    {
+        let mut entries: Vec<_> = map.into_iter().collect();
+
+        if order == Order::Sorted {
+            entries.sort_by(|a, b| a.0.borrow().cmp(b.0.borrow()));
+        }
+
+        Self { entries }
+    }
+}
+
+impl<K, V> fmt::Debug for Entries<'_, K, V>
+where
+    K: Borrow<str>,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map()
+            .entries(self.entries.iter().map(|(k, v)| ((*k).borrow(), v)))
+            .finish()
+    }
+}
+
+impl<K, V> Render for Entries<'_, K, V>
+where
+    K: Borrow<str>,
+    V: Render,
+{
+    #[inline]
+    fn is_truthy(&self, truthiness: &Truthiness) -> bool {
+        truthiness.map(self.entries.is_empty())
+    }
+
+    #[inline]
+    fn render_section(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        let len = self.entries.len();
+
+        for (index, (key, value)) in self.entries.iter().enumerate() {
+            let entry = Entry {
+                key: (*key).borrow(),
+                value: *value,
+            };
+
+            Iteration::new(index, Some(index + 1 == len)).render(&entry, context, writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A single map entry, pushed for each iteration of an `Entries` section.
+#[derive(Debug)]
+struct Entry<'a> {
+    key: &'a str,
+    value: &'a dyn Render,
+}
+
+impl Render for Entry<'_> {
+    #[inline]
+    fn lookup(&self, key: &str) -> Option<&dyn Render> {
+        match key {
+            "key" => Some(&self.key),
+            "value" => Some(self.value),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,10 @@
+/// A date or time rendered with a custom format, rather than its default ISO
+/// 8601 form.
+///
+/// The format is a `strftime` pattern such as `"%d %B %Y"` for chrono types.
+/// For time types it's a format description, parsed once up front with
+/// `time::format_description::parse_borrowed::<2>` or the `format_description!`
+/// macro, rather than each time the value is rendered. A format the value
+/// can't be rendered with is reported as an error when rendering.
+#[derive(Debug, Clone, Copy)]
+pub struct Formatted<T, F>(pub T, pub F);
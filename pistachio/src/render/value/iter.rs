@@ -0,0 +1,87 @@
+use std::fmt;
+
+use crate::{
+    error::Error,
+    render::{
+        Context,
+        Iteration,
+        Render,
+        Truthiness,
+        Writer,
+    },
+};
+
+/// A cloneable iterator that's rendered as a sequence, without collecting it.
+///
+/// Each section iterates a fresh clone, and truthiness only peeks at the first
+/// item of a clone, so the wrapped iterator is never consumed.
+pub struct Iter<I>(pub I);
+
+/// A closure returning an [`IntoIterator`], which is invoked every time the
+/// sequence is rendered or tested for truthiness.
+///
+/// This suits iterators that can't be cloned, such as database cursors.
+pub struct IterFn<F>(pub F);
+
+impl<I> fmt::Debug for Iter<I> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Iter")
+    }
+}
+
+impl<F> fmt::Debug for IterFn<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("IterFn")
+    }
+}
+
+impl<I> Render for Iter<I>
+where
+    I: Iterator + Clone,
+    I::Item: Render,
+{
+    #[inline]
+    fn is_truthy(&self, truthiness: &Truthiness) -> bool {
+        truthiness.sequence(self.0.clone().next().is_none())
+    }
+
+    #[inline]
+    fn render_section(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        render_items(self.0.clone(), context, writer)
+    }
+}
+
+impl<F, I> Render for IterFn<F>
+where
+    F: Fn() -> I,
+    I: IntoIterator,
+    I::Item: Render,
+{
+    #[inline]
+    fn is_truthy(&self, truthiness: &Truthiness) -> bool {
+        truthiness.sequence((self.0)().into_iter().next().is_none())
+    }
+
+    #[inline]
+    fn render_section(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        render_items((self.0)(), context, writer)
+    }
+}
+
+/// Render the section body for each item, peeking ahead to know which is last.
+#[inline]
+fn render_items<I>(items: I, context: Context, writer: &mut Writer) -> Result<(), Error>
+where
+    I: IntoIterator,
+    I::Item: Render,
+{
+    let mut items = items.into_iter().enumerate().peekable();
+
+    while let Some((index, item)) = items.next() {
+        let last = items.peek().is_none();
+
+        Iteration::new(index, Some(last)).render(&item, context, writer)?;
+    }
+
+    Ok(())
+}
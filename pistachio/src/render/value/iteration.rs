@@ -0,0 +1,117 @@
+use crate::{
+    error::Error,
+    render::{
+        Context,
+        Render,
+        Writer,
+    },
+};
+
+/// Metadata about the current item of a section over a sequence, which is
+/// pushed beneath each item so it can be resolved from the section body:
+///
+/// * `@index` - the zero-based index of the item.
+/// * `@number` - the one-based index of the item.
+/// * `@first` and `@last` - whether this is the first or last item.
+/// * `@odd` and `@even` - whether the one-based index is odd or even, so the
+///   first item is odd.
+///
+/// Other names are never resolved, so they fall through to the frames below.
+#[derive(Debug, Clone, Copy)]
+pub struct Iteration {
+    index: usize,
+    /// Whether this is the last item, if known.
+    last: Option<bool>,
+}
+
+impl Iteration {
+    #[inline]
+    pub fn new(index: usize, last: Option<bool>) -> Self {
+        Self { index, last }
+    }
+
+    /// Render the section body for `item`, with this metadata beneath it.
+    #[inline]
+    pub fn render(
+        self,
+        item: &dyn Render,
+        context: Context,
+        writer: &mut Writer,
+    ) -> Result<(), Error> {
+        context.push(&self).push(item).render_to_writer(writer)
+    }
+
+    #[inline]
+    fn render_named<F>(&self, name: &[&str], render: F) -> Result<bool, Error>
+    where
+        F: FnOnce(&dyn Render, &[&str]) -> Result<bool, Error>,
+    {
+        let (key, rest) = match name.split_first() {
+            Some(split) => split,
+            None => return Ok(false),
+        };
+
+        match *key {
+            "@index" => render(&self.index, rest),
+            "@number" => render(&(self.index + 1), rest),
+            "@first" => render(&(self.index == 0), rest),
+            "@last" => match self.last {
+                Some(last) => render(&last, rest),
+                None => Ok(false),
+            },
+            "@odd" => render(&self.index.is_multiple_of(2), rest),
+            "@even" => render(&!self.index.is_multiple_of(2), rest),
+            _ => Ok(false),
+        }
+    }
+}
+
+impl Render for Iteration {
+    #[inline]
+    fn render_named_escaped(
+        &self,
+        name: &[&str],
+        context: Context,
+        writer: &mut Writer,
+    ) -> Result<bool, Error> {
+        self.render_named(name, |value, name| {
+            value.render_named_escaped(name, context.push(value), writer)
+        })
+    }
+
+    #[inline]
+    fn render_named_unescaped(
+        &self,
+        name: &[&str],
+        context: Context,
+        writer: &mut Writer,
+    ) -> Result<bool, Error> {
+        self.render_named(name, |value, name| {
+            value.render_named_unescaped(name, context.push(value), writer)
+        })
+    }
+
+    #[inline]
+    fn render_named_section(
+        &self,
+        name: &[&str],
+        context: Context,
+        writer: &mut Writer,
+    ) -> Result<bool, Error> {
+        self.render_named(name, |value, name| {
+            value.render_named_section(name, context.push(value), writer)
+        })
+    }
+
+    #[inline]
+    fn render_named_inverted(
+        &self,
+        name: &[&str],
+        context: Context,
+        writer: &mut Writer,
+    ) -> Result<bool, Error> {
+        self.render_named(name, |value, name| {
+            value.render_named_inverted(name, context.push(value), writer)
+        })
+    }
+}
@@ -10,6 +10,7 @@ use crate::{
     render::{
         Context,
         Render,
+        Truthiness,
         Writer,
     },
     Template,
@@ -29,21 +30,36 @@ impl Render for Value {
     }
 
     #[inline]
-    fn is_truthy(&self) -> bool {
+    fn is_truthy(&self, truthiness: &Truthiness) -> bool {
         match self {
-            Value::Null => ().is_truthy(),
-            Value::Bool(b) => b.is_truthy(),
-            Value::Number(n) => n.is_truthy(),
-            Value::String(s) => s.is_truthy(),
-            Value::Array(v) => v.is_truthy(),
-            Value::Object(m) => m.is_truthy(),
+            Value::Null => ().is_truthy(truthiness),
+            Value::Bool(b) => b.is_truthy(truthiness),
+            Value::Number(n) => n.is_truthy(truthiness),
+            Value::String(s) => s.is_truthy(truthiness),
+            Value::Array(v) => v.is_truthy(truthiness),
+            Value::Object(m) => m.is_truthy(truthiness),
         }
     }
 
     #[inline]
-    fn render_escaped(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
-        // println!("json:render_escaped {:?}", self);
+    fn lookup(&self, key: &str) -> Option<&dyn Render> {
+        match self {
+            Value::Array(v) => v.lookup(key),
+            Value::Object(m) => m.lookup(key),
+            _ => None,
+        }
+    }
 
+    #[inline]
+    fn length(&self) -> Option<usize> {
+        match self {
+            Value::Array(v) => v.length(),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn render_escaped(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
         match self {
             Value::Null => ().render_escaped(context, writer),
             Value::Bool(b) => b.render_escaped(context, writer),
@@ -56,8 +72,6 @@ impl Render for Value {
 
     #[inline]
     fn render_unescaped(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
-        // println!("json:render_unescaped {:?}", self);
-
         match self {
             Value::Null => ().render_unescaped(context, writer),
             Value::Bool(b) => b.render_unescaped(context, writer),
@@ -70,8 +84,6 @@ impl Render for Value {
 
     #[inline]
     fn render_section(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
-        // println!("json:render_section {:?}", self);
-
         match self {
             Value::Null => ().render_section(context, writer),
             Value::Bool(b) => b.render_section(context, writer),
@@ -84,8 +96,6 @@ impl Render for Value {
 
     #[inline]
     fn render_inverted(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
-        // println!("json:render_inverted {:?}", self);
-
         match self {
             Value::Null => ().render_inverted(context, writer),
             Value::Bool(b) => b.render_inverted(context, writer),
@@ -95,82 +105,6 @@ impl Render for Value {
             Value::Object(m) => m.render_inverted(context, writer),
         }
     }
-
-    #[inline]
-    fn render_named_escaped(
-        &self,
-        name: &[&str],
-        context: Context,
-        writer: &mut Writer,
-    ) -> Result<bool, Error> {
-        // println!("json:render_named_escaped {:?}", self);
-
-        match self {
-            Value::Null => ().render_named_escaped(name, context, writer),
-            Value::Bool(b) => b.render_named_escaped(name, context, writer),
-            Value::Number(n) => n.render_named_escaped(name, context, writer),
-            Value::String(s) => s.render_named_escaped(name, context, writer),
-            Value::Array(v) => v.render_named_escaped(name, context, writer),
-            Value::Object(m) => m.render_named_escaped(name, context, writer),
-        }
-    }
-
-    #[inline]
-    fn render_named_unescaped(
-        &self,
-        name: &[&str],
-        context: Context,
-        writer: &mut Writer,
-    ) -> Result<bool, Error> {
-        // println!("json:render_named_unescaped {:?}", self);
-
-        match self {
-            Value::Null => ().render_named_unescaped(name, context, writer),
-            Value::Bool(b) => b.render_named_unescaped(name, context, writer),
-            Value::Number(n) => n.render_named_unescaped(name, context, writer),
-            Value::String(s) => s.render_named_unescaped(name, context, writer),
-            Value::Array(v) => v.render_named_unescaped(name, context, writer),
-            Value::Object(m) => m.render_named_unescaped(name, context, writer),
-        }
-    }
-
-    #[inline]
-    fn render_named_section(
-        &self,
-        name: &[&str],
-        context: Context,
-        writer: &mut Writer,
-    ) -> Result<bool, Error> {
-        // println!("json:render_named_section {:?}", self);
-
-        match self {
-            Value::Null => ().render_named_section(name, context, writer),
-            Value::Bool(b) => b.render_named_section(name, context, writer),
-            Value::Number(n) => n.render_named_section(name, context, writer),
-            Value::String(s) => s.render_named_section(name, context, writer),
-            Value::Array(v) => v.render_named_section(name, context, writer),
-            Value::Object(m) => m.render_named_section(name, context, writer),
-        }
-    }
-
-    #[inline]
-    fn render_named_inverted(
-        &self,
-        name: &[&str],
-        context: Context,
-        writer: &mut Writer,
-    ) -> Result<bool, Error> {
-        // println!("json:render_named_inverted {} {:?}", name, self);
-
-        match self {
-            Value::Null => ().render_named_inverted(name, context, writer),
-            Value::Bool(b) => b.render_named_inverted(name, context, writer),
-            Value::Number(n) => n.render_named_inverted(name, context, writer),
-            Value::String(s) => s.render_named_inverted(name, context, writer),
-            Value::Array(v) => v.render_named_inverted(name, context, writer),
-            Value::Object(m) => m.render_named_inverted(name, context, writer),
-        }
-    }
 }
 
 impl Render for Map<String, Value> {
@@ -180,25 +114,25 @@ impl Render for Map<String, Value> {
 impl Render for Number {
     #[inline]
     fn size_hint(&self, template: &Template) -> usize {
-        if let Some(n) = self.as_f64() {
-            n.size_hint(template)
-        } else if let Some(n) = self.as_u64() {
+        if let Some(n) = self.as_u64() {
             n.size_hint(template)
         } else if let Some(n) = self.as_i64() {
             n.size_hint(template)
+        } else if let Some(n) = self.as_f64() {
+            n.size_hint(template)
         } else {
             0
         }
     }
 
     #[inline]
-    fn is_truthy(&self) -> bool {
-        if let Some(n) = self.as_f64() {
-            n.is_truthy()
-        } else if let Some(n) = self.as_u64() {
-            n.is_truthy()
+    fn is_truthy(&self, truthiness: &Truthiness) -> bool {
+        if let Some(n) = self.as_u64() {
+            n.is_truthy(truthiness)
         } else if let Some(n) = self.as_i64() {
-            n.is_truthy()
+            n.is_truthy(truthiness)
+        } else if let Some(n) = self.as_f64() {
+            n.is_truthy(truthiness)
         } else {
             false
         }
@@ -206,13 +140,13 @@ impl Render for Number {
 
     #[inline]
     fn render_escaped(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
-        println!("number:render_escaped {:?}", self);
-        if let Some(n) = self.as_f64() {
-            n.render_escaped(context, writer)
-        } else if let Some(n) = self.as_u64() {
+        // Integers are checked first, since `as_f64` also converts them.
+        if let Some(n) = self.as_u64() {
             n.render_escaped(context, writer)
         } else if let Some(n) = self.as_i64() {
             n.render_escaped(context, writer)
+        } else if let Some(n) = self.as_f64() {
+            n.render_escaped(context, writer)
         } else {
             Ok(())
         }
@@ -1,3 +1,5 @@
+use std::fmt;
+
 use crate::{
     error::Error,
     render::{
@@ -7,63 +9,74 @@ use crate::{
     },
 };
 
-// macro_rules! impl_arity_zero {
-//     ( $($ty:ty),* ) => {
-//         $(
-//             impl<T: Render> Render for $ty {
-//                 #[inline]
-//                 fn render_escaped(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
-//                     self().render_escaped(context, writer)
-//                 }
+/// A zero-arity closure that's invoked every time it's interpolated by `{{lambda}}`.
+///
+/// The returned text is parsed as a template using the default delimiters, and
+/// rendered against the current context.
+pub struct Lambda<F>(pub F);
+
+/// A closure that's invoked with the unrendered content of a
+/// `{{#lambda}}..{{/lambda}}` section.
+///
+/// The returned text is parsed as a template using the delimiters in effect at
+/// the section tag, and rendered in place of the section.
+pub struct SectionLambda<F>(pub F);
+
+impl<F> fmt::Debug for Lambda<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Lambda")
+    }
+}
+
+impl<F> fmt::Debug for SectionLambda<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("SectionLambda")
+    }
+}
+
+impl<F, S> Render for Lambda<F>
+where
+    F: Fn() -> S,
+    S: AsRef<str>,
+{
+    #[inline]
+    fn render_escaped(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        let text = context.render_source_to_string((self.0)().as_ref())?;
 
-//                 #[inline]
-//                 fn render_unescaped(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
-//                     self().render_unescaped(context, writer)
-//                 }
+        writer.write_escaped(&text)
+    }
 
-//                 #[inline]
-//                 fn render_section(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
-//                     let result = self();
-//                     if result.is_truthy() {
-//                         context.push(&result).render_to_writer(writer)?;
-//                     }
+    #[inline]
+    fn render_unescaped(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        let text = context.render_source_to_string((self.0)().as_ref())?;
 
-//                     Ok(())
-//                 }
+        writer.write_unescaped(&text)
+    }
 
-//                 #[inline]
-//                 fn render_inverted(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
-//                     let result = self();
-//                     if !result.is_truthy() {
-//                         context.push(&result).render_to_writer(writer)?;
-//                     }
+    #[inline]
+    fn render_section(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        let text = context.render_source_to_string((self.0)().as_ref())?;
 
-//                     Ok(())
-//                 }
-//             }
-//         )*
-//     };
-// }
+        text.render_section(context, writer)
+    }
 
-// impl_arity_zero!(fn() -> T, dyn Fn() -> T);
+    #[inline]
+    fn render_inverted(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        let text = context.render_source_to_string((self.0)().as_ref())?;
 
-// macro_rules! impl_arity_string {
-//     ( $($ty:ty),* ) => {
-//         $(
-//             impl<T: Render> Render for $ty {
-//                 #[inline]
-//                 fn render_section(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
-//                     let source = context.render(8)?;
-//                     let result = self(source);
-//                     if result.is_truthy() {
-//                         context.push(&result).render_to_writer(writer)?;
-//                     }
+        text.render_inverted(context, writer)
+    }
+}
 
-//                     Ok(())
-//                 }
-//             }
-//         )*
-//     };
-// }
+impl<F, S> Render for SectionLambda<F>
+where
+    F: Fn(&str) -> S,
+    S: AsRef<str>,
+{
+    #[inline]
+    fn render_section(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        let text = (self.0)(context.section_source());
 
-// impl_arity_string!(fn(String) -> T, dyn Fn(String) -> T);
+        context.render_section_source(text.as_ref(), writer)
+    }
+}
@@ -0,0 +1,147 @@
+use std::{
+    fmt,
+    sync::OnceLock,
+};
+
+use crate::{
+    error::Error,
+    render::{
+        Context,
+        Render,
+        Truthiness,
+        Writer,
+    },
+    Template,
+};
+
+/// A value computed by a closure the first time a template resolves it, and
+/// rendered as if it were that value thereafter.
+///
+/// The closure is invoked at most once, so a value that is never referenced by
+/// a template is never computed.
+pub struct Lazy<T, F = fn() -> T> {
+    value: OnceLock<T>,
+    init: F,
+}
+
+impl<T, F> Lazy<T, F>
+where
+    F: Fn() -> T,
+{
+    pub const fn new(init: F) -> Self {
+        Self {
+            value: OnceLock::new(),
+            init,
+        }
+    }
+
+    /// The value, computing it if this is the first access.
+    #[inline]
+    pub fn get(&self) -> &T {
+        self.value.get_or_init(&self.init)
+    }
+}
+
+impl<T: fmt::Debug, F> fmt::Debug for Lazy<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.value.get() {
+            Some(value) => f.debug_tuple("Lazy").field(value).finish(),
+            None => f.write_str("Lazy(<uninit>)"),
+        }
+    }
+}
+
+impl<T, F> Render for Lazy<T, F>
+where
+    T: Render,
+    F: Fn() -> T,
+{
+    // Only hint at the size of a value that has already been computed, since
+    // the hint is taken before rendering whether or not the value is used.
+    #[inline]
+    fn size_hint(&self, template: &Template) -> usize {
+        self.value
+            .get()
+            .map_or(0, |value| value.size_hint(template))
+    }
+
+    #[inline]
+    fn is_truthy(&self, truthiness: &Truthiness) -> bool {
+        self.get().is_truthy(truthiness)
+    }
+
+    #[inline]
+    fn lookup(&self, key: &str) -> Option<&dyn Render> {
+        self.get().lookup(key)
+    }
+
+    #[inline]
+    fn length(&self) -> Option<usize> {
+        self.get().length()
+    }
+
+    #[inline]
+    fn contains_key(&self, key: &str) -> bool {
+        self.get().contains_key(key)
+    }
+
+    #[inline]
+    fn render_escaped(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        self.get().render_escaped(context, writer)
+    }
+
+    #[inline]
+    fn render_unescaped(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        self.get().render_unescaped(context, writer)
+    }
+
+    #[inline]
+    fn render_section(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        self.get().render_section(context, writer)
+    }
+
+    #[inline]
+    fn render_inverted(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        self.get().render_inverted(context, writer)
+    }
+
+    #[inline]
+    fn render_named_escaped(
+        &self,
+        name: &[&str],
+        context: Context,
+        writer: &mut Writer,
+    ) -> Result<bool, Error> {
+        self.get().render_named_escaped(name, context, writer)
+    }
+
+    #[inline]
+    fn render_named_unescaped(
+        &self,
+        name: &[&str],
+        context: Context,
+        writer: &mut Writer,
+    ) -> Result<bool, Error> {
+        self.get().render_named_unescaped(name, context, writer)
+    }
+
+    #[inline]
+    fn render_named_section(
+        &self,
+        name: &[&str],
+        context: Context,
+        writer: &mut Writer,
+    ) -> Result<bool, Error> {
+        self.get().render_named_section(name, context, writer)
+    }
+
+    #[inline]
+    fn render_named_inverted(
+        &self,
+        name: &[&str],
+        context: Context,
+        writer: &mut Writer,
+    ) -> Result<bool, Error> {
+        self.get().render_named_inverted(name, context, writer)
+    }
+}
@@ -1,8 +1,20 @@
+use std::{
+    ffi::{
+        OsStr,
+        OsString,
+    },
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
 use crate::{
     error::Error,
     render::{
         Context,
         Render,
+        Truthiness,
         Writer,
     },
     Template,
@@ -10,14 +22,14 @@ use crate::{
 
 impl Render for () {
     #[inline]
-    fn is_truthy(&self) -> bool {
+    fn is_truthy(&self, _truthiness: &Truthiness) -> bool {
         false
     }
 }
 
 impl Render for bool {
     #[inline]
-    fn is_truthy(&self) -> bool {
+    fn is_truthy(&self, _truthiness: &Truthiness) -> bool {
         *self
     }
 
@@ -39,8 +51,8 @@ impl Render for String {
     }
 
     #[inline]
-    fn is_truthy(&self) -> bool {
-        !self.is_empty()
+    fn is_truthy(&self, truthiness: &Truthiness) -> bool {
+        truthiness.string(self)
     }
 
     #[inline]
@@ -61,8 +73,8 @@ impl Render for str {
     }
 
     #[inline]
-    fn is_truthy(&self) -> bool {
-        !self.is_empty()
+    fn is_truthy(&self, truthiness: &Truthiness) -> bool {
+        truthiness.string(self)
     }
 
     #[inline]
@@ -75,3 +87,51 @@ impl Render for str {
         writer.write_unescaped(self)
     }
 }
+
+impl Render for char {
+    #[inline]
+    fn size_hint(&self, _template: &Template) -> usize {
+        self.len_utf8()
+    }
+
+    #[inline]
+    fn render_escaped(&self, _context: Context, writer: &mut Writer) -> Result<(), Error> {
+        writer.write_escaped(self.encode_utf8(&mut [0; 4]))
+    }
+
+    #[inline]
+    fn render_unescaped(&self, _context: Context, writer: &mut Writer) -> Result<(), Error> {
+        writer.write_unescaped(self.encode_utf8(&mut [0; 4]))
+    }
+}
+
+// Paths and OS strings aren't necessarily UTF-8, so are rendered lossily.
+macro_rules! impl_os_str {
+    ( $($ty:ty),* ) => {
+        $(
+            impl Render for $ty {
+                #[inline]
+                fn size_hint(&self, _template: &Template) -> usize {
+                    AsRef::<OsStr>::as_ref(self).len()
+                }
+
+                #[inline]
+                fn is_truthy(&self, truthiness: &Truthiness) -> bool {
+                    truthiness.string(&AsRef::<OsStr>::as_ref(self).to_string_lossy())
+                }
+
+                #[inline]
+                fn render_escaped(&self, _context: Context, writer: &mut Writer) -> Result<(), Error> {
+                    writer.write_escaped(&AsRef::<OsStr>::as_ref(self).to_string_lossy())
+                }
+
+                #[inline]
+                fn render_unescaped(&self, _context: Context, writer: &mut Writer) -> Result<(), Error> {
+                    writer.write_unescaped(&AsRef::<OsStr>::as_ref(self).to_string_lossy())
+                }
+            }
+        )*
+    };
+}
+
+impl_os_str!(OsStr, OsString, Path, PathBuf);
@@ -1,13 +1,168 @@
+use std::{
+    fmt,
+    num::{
+        NonZeroI128,
+        NonZeroI16,
+        NonZeroI32,
+        NonZeroI64,
+        NonZeroI8,
+        NonZeroIsize,
+        NonZeroU128,
+        NonZeroU16,
+        NonZeroU32,
+        NonZeroU64,
+        NonZeroU8,
+        NonZeroUsize,
+    },
+    str::FromStr,
+};
+
 use crate::{
     error::Error,
     render::{
         Context,
         Render,
+        Truthiness,
         Writer,
     },
     Template,
 };
 
+/// Options for rendering numbers, configured with
+/// [`Builder::number_format`](crate::Builder::number_format).
+///
+/// Integers are always rendered exactly, and are only affected by the
+/// thousands separator. By default numbers render as their `Display` form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumberFormat {
+    decimal_places: Option<usize>,
+    significant_digits: Option<usize>,
+    thousands_separator: Option<char>,
+    decimal_separator: char,
+}
+
+pub(crate) static DEFAULT_NUMBER_FORMAT: NumberFormat = NumberFormat::new();
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NumberFormat {
+    pub const fn new() -> Self {
+        Self {
+            decimal_places: None,
+            significant_digits: None,
+            thousands_separator: None,
+            decimal_separator: '.',
+        }
+    }
+
+    /// Render floats with exactly this many decimal places.
+    pub fn decimal_places(mut self, places: usize) -> Self {
+        self.decimal_places = Some(places);
+        self
+    }
+
+    /// Round floats to at most this many significant digits, so `0.1 + 0.2`
+    /// renders as `0.3` with 15 or fewer. Applied before `decimal_places`.
+    pub fn significant_digits(mut self, digits: usize) -> Self {
+        self.significant_digits = Some(digits.max(1));
+        self
+    }
+
+    /// Separate groups of thousands in the integer part, as in `1,234,567`.
+    pub fn thousands_separator(mut self, separator: char) -> Self {
+        self.thousands_separator = Some(separator);
+        self
+    }
+
+    /// Separate the integer and fractional parts with this, rather than `.`.
+    pub fn decimal_separator(mut self, separator: char) -> Self {
+        self.decimal_separator = separator;
+        self
+    }
+
+    #[inline]
+    fn is_default(&self) -> bool {
+        *self == DEFAULT_NUMBER_FORMAT
+    }
+
+    #[inline]
+    fn write_integer(&self, value: impl fmt::Display, writer: &mut Writer) -> Result<(), Error> {
+        if self.thousands_separator.is_none() {
+            return writer.format_unescaped(value);
+        }
+
+        writer.write_escaped(&self.separate(&value.to_string()))
+    }
+
+    #[inline]
+    fn write_float<T>(&self, value: T, writer: &mut Writer) -> Result<(), Error>
+    where
+        T: Copy + fmt::Display + fmt::LowerExp + FromStr,
+    {
+        if self.is_default() {
+            return writer.format_unescaped(value);
+        }
+
+        // Round-trip through scientific notation, which rounds to the given
+        // number of significant digits, so `Display` drops any trailing noise.
+        let value = match self.significant_digits {
+            Some(digits) => format!("{:.*e}", digits - 1, value)
+                .parse()
+                .unwrap_or(value),
+            None => value,
+        };
+
+        let text = match self.decimal_places {
+            Some(places) => format!("{:.*}", places, value),
+            None => value.to_string(),
+        };
+
+        writer.write_escaped(&self.separate(&text))
+    }
+
+    /// Apply the separators to a formatted number, leaving non-finite values
+    /// such as `NaN` and `inf` untouched.
+    fn separate(&self, text: &str) -> String {
+        let (sign, unsigned) = match text.strip_prefix('-') {
+            Some(unsigned) => ("-", unsigned),
+            None => ("", text),
+        };
+
+        let (integer, fraction) = match unsigned.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (unsigned, None),
+        };
+
+        if !integer.bytes().all(|byte| byte.is_ascii_digit()) {
+            return text.to_string();
+        }
+
+        let mut separated = String::with_capacity(text.len() + integer.len() / 3);
+        separated.push_str(sign);
+
+        for (index, digit) in integer.chars().enumerate() {
+            if let Some(separator) = self.thousands_separator {
+                if index > 0 && (integer.len() - index) % 3 == 0 {
+                    separated.push(separator);
+                }
+            }
+
+            separated.push(digit);
+        }
+
+        if let Some(fraction) = fraction {
+            separated.push(self.decimal_separator);
+            separated.push_str(fraction);
+        }
+
+        separated
+    }
+}
+
 macro_rules! impl_numbers {
     ( $( $ty:ty ),* ) => {
         $(
@@ -18,17 +173,17 @@ macro_rules! impl_numbers {
                 }
 
                 #[inline]
-                fn is_truthy(&self) -> bool {
-                    *self != 0 as $ty
+                fn is_truthy(&self, truthiness: &Truthiness) -> bool {
+                    truthiness.number(*self == 0 as $ty)
                 }
 
                 #[inline]
                 fn render_escaped(
                     &self,
-                    _context: Context,
+                    context: Context,
                     writer: &mut Writer
                 ) -> Result<(), Error> {
-                    writer.format_unescaped(self)
+                    context.number_format().write_integer(self, writer)
                 }
             }
         )*
@@ -48,17 +203,17 @@ macro_rules! impl_float {
                 }
 
                 #[inline]
-                fn is_truthy(&self) -> bool {
-                    self.abs() > $epsilon
+                fn is_truthy(&self, truthiness: &Truthiness) -> bool {
+                    truthiness.float(*self as f64, $epsilon as f64)
                 }
 
                 #[inline]
                 fn render_escaped(
                     &self,
-                    _context: Context,
+                    context: Context,
                     writer: &mut Writer
                 ) -> Result<(), Error> {
-                    writer.format_unescaped(self)
+                    context.number_format().write_float(*self, writer)
                 }
             }
         )*
@@ -66,3 +221,42 @@ macro_rules! impl_float {
 }
 
 impl_float!(f32: f32::EPSILON, f64: f64::EPSILON);
+
+macro_rules! impl_nonzero {
+    ( $( $ty:ty ),* ) => {
+        $(
+            impl Render for $ty {
+                #[inline]
+                fn size_hint(&self, template: &Template) -> usize {
+                    self.get().size_hint(template)
+                }
+
+                #[inline]
+                fn render_escaped(
+                    &self,
+                    context: Context,
+                    writer: &mut Writer
+                ) -> Result<(), Error> {
+                    self.get().render_escaped(context, writer)
+                }
+            }
+        )*
+    };
+}
+
+impl_nonzero!(
+    NonZeroU8,
+    NonZeroU16,
+    NonZeroU32,
+    NonZeroU64,
+    NonZeroU128,
+    NonZeroUsize
+);
+impl_nonzero!(
+    NonZeroI8,
+    NonZeroI16,
+    NonZeroI32,
+    NonZeroI64,
+    NonZeroI128,
+    NonZeroIsize
+);
@@ -1,9 +1,20 @@
 use std::{
     borrow::Cow,
+    cell::{
+        Cell,
+        Ref,
+        RefCell,
+    },
     fmt,
     ops::Deref,
     rc::Rc,
-    sync::Arc,
+    sync::{
+        Arc,
+        Mutex,
+        RwLock,
+        TryLockError,
+        TryLockResult,
+    },
 };
 
 use crate::{
@@ -11,99 +22,117 @@ use crate::{
     render::{
         Context,
         Render,
+        Truthiness,
         Writer,
     },
     Template,
 };
 
-macro_rules! impl_pointers {
-    ( $($ty:ty $(: $bounds:ident)?),* ) => {
-        $(
-            impl<T: Render $(+ $bounds)? + ?Sized> Render for $ty {
-                #[inline]
-                fn size_hint(&self, template: &Template) -> usize {
-                    self.deref().size_hint(template)
-                }
+/// Implements every method by delegating to `$target`, where `$this` is `self`.
+macro_rules! impl_delegate {
+    ( |$this:ident| $target:expr ) => {
+        #[inline]
+        fn size_hint(&self, template: &Template) -> usize {
+            let $this = self;
+            $target.size_hint(template)
+        }
 
-                #[inline]
-                fn is_truthy(&self) -> bool {
-                    self.deref().is_truthy()
-                }
+        #[inline]
+        fn is_truthy(&self, truthiness: &Truthiness) -> bool {
+            let $this = self;
+            $target.is_truthy(truthiness)
+        }
 
-                #[inline]
-                fn render_escaped(
-                    &self,
-                    context: Context,
-                    writer: &mut Writer,
-                ) -> Result<(), Error> {
-                    self.deref().render_escaped(context, writer)
-                }
+        #[inline]
+        fn length(&self) -> Option<usize> {
+            let $this = self;
+            $target.length()
+        }
+        #[inline]
+        fn contains_key(&self, key: &str) -> bool {
+            let $this = self;
+            $target.contains_key(key)
+        }
 
-                #[inline]
-                fn render_unescaped(
-                    &self,
-                    context: Context,
-                    writer: &mut Writer,
-                ) -> Result<(), Error> {
-                    self.deref().render_unescaped(context, writer)
-                }
+        #[inline]
+        fn render_escaped(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+            let $this = self;
+            $target.render_escaped(context, writer)
+        }
 
-                #[inline]
-                fn render_section(
-                    &self,
-                    context: Context,
-                    writer: &mut Writer,
-                ) -> Result<(), Error> {
-                    self.deref().render_section(context, writer)
-                }
+        #[inline]
+        fn render_unescaped(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+            let $this = self;
+            $target.render_unescaped(context, writer)
+        }
 
-                #[inline]
-                fn render_inverted(
-                    &self,
-                    context: Context,
-                    writer: &mut Writer,
-                ) -> Result<(), Error> {
-                    self.deref().render_inverted(context, writer)
-                }
+        #[inline]
+        fn render_section(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+            let $this = self;
+            $target.render_section(context, writer)
+        }
 
-                #[inline]
-                fn render_named_escaped(
-                    &self,
-                    name: &[&str],
-                    context: Context,
-                    writer: &mut Writer,
-                ) -> Result<bool, Error> {
-                    self.deref().render_named_escaped(name, context, writer)
-                }
+        #[inline]
+        fn render_inverted(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+            let $this = self;
+            $target.render_inverted(context, writer)
+        }
 
-                #[inline]
-                fn render_named_unescaped(
-                    &self,
-                    name: &[&str],
-                    context: Context,
-                    writer: &mut Writer,
-                ) -> Result<bool, Error> {
-                    self.deref().render_named_unescaped(name, context, writer)
-                }
+        #[inline]
+        fn render_named_escaped(
+            &self,
+            name: &[&str],
+            context: Context,
+            writer: &mut Writer,
+        ) -> Result<bool, Error> {
+            let $this = self;
+            $target.render_named_escaped(name, context, writer)
+        }
 
-                #[inline]
-                fn render_named_section(
-                    &self,
-                    name: &[&str],
-                    context: Context,
-                    writer: &mut Writer,
-                ) -> Result<bool, Error> {
-                    self.deref().render_named_section(name, context, writer)
-                }
+        #[inline]
+        fn render_named_unescaped(
+            &self,
+            name: &[&str],
+            context: Context,
+            writer: &mut Writer,
+        ) -> Result<bool, Error> {
+            let $this = self;
+            $target.render_named_unescaped(name, context, writer)
+        }
+
+        #[inline]
+        fn render_named_section(
+            &self,
+            name: &[&str],
+            context: Context,
+            writer: &mut Writer,
+        ) -> Result<bool, Error> {
+            let $this = self;
+            $target.render_named_section(name, context, writer)
+        }
+
+        #[inline]
+        fn render_named_inverted(
+            &self,
+            name: &[&str],
+            context: Context,
+            writer: &mut Writer,
+        ) -> Result<bool, Error> {
+            let $this = self;
+            $target.render_named_inverted(name, context, writer)
+        }
+    };
+}
+
+macro_rules! impl_pointers {
+    ( $($ty:ty),* ) => {
+        $(
+            impl<T: Render + ?Sized> Render for $ty {
+                impl_delegate!(|this| this.deref());
 
                 #[inline]
-                fn render_named_inverted(
-                    &self,
-                    name: &[&str],
-                    context: Context,
-                    writer: &mut Writer,
-                ) -> Result<bool, Error> {
-                    self.deref().render_named_inverted(name, context, writer)
+                fn lookup(&self, key: &str) -> Option<&dyn Render> {
+                    self.deref().lookup(key)
                 }
             }
         )*
@@ -115,5 +144,119 @@ impl_pointers! {
     Box<T>,
     Rc<T>,
     Arc<T>
-    // Cow<'_, T>: ToOwned
+}
+
+impl<T> Render for Cow<'_, T>
+where
+    T: Render + ToOwned + ?Sized,
+    T::Owned: fmt::Debug,
+{
+    impl_delegate!(|this| this.deref());
+
+    #[inline]
+    fn lookup(&self, key: &str) -> Option<&dyn Render> {
+        self.deref().lookup(key)
+    }
+}
+
+impl<T: Render + Copy> Render for Cell<T> {
+    impl_delegate!(|this| this.get());
+}
+
+/// Implements `Render` for a lock, which is acquired without blocking each time
+/// it's rendered. The guarded value is rendered in its place, and replaces it
+/// at the top of the stack, so names inside a section entered through the lock
+/// resolve against the value without locking again.
+///
+/// A lock is only ever called as the top frame, or wrapped by it, since each
+/// frame is replaced by its guard while anything above it renders. A lock
+/// that's already held raises [`Error::LockUnavailable`] rather than
+/// deadlocking, such as when `{{lock.name}}` reaches the same lock again from
+/// within `{{#lock}}`.
+macro_rules! impl_lock {
+    ( $ty:ident, |$this:ident| $acquire:expr ) => {
+        impl<T: Render + ?Sized> Render for $ty<T> {
+            #[inline]
+            fn size_hint(&self, template: &Template) -> usize {
+                let $this = self;
+                $acquire.map_or(0, |value| value.size_hint(template))
+            }
+
+            // A lock that's held elsewhere is truthy, so that rendering through
+            // it raises an error rather than silently skipping it.
+            #[inline]
+            fn is_truthy(&self, truthiness: &Truthiness) -> bool {
+                let $this = self;
+                $acquire.map_or(true, |value| value.is_truthy(truthiness))
+            }
+
+            #[inline]
+            fn length(&self) -> Option<usize> {
+                let $this = self;
+                $acquire.ok().and_then(|value| value.length())
+            }
+            #[inline]
+            fn contains_key(&self, key: &str) -> bool {
+                let $this = self;
+                $acquire.is_ok_and(|value| value.contains_key(key))
+            }
+
+            impl_lock_method!(|$this| $acquire, render_escaped);
+            impl_lock_method!(|$this| $acquire, render_unescaped);
+            impl_lock_method!(|$this| $acquire, render_section);
+            impl_lock_method!(|$this| $acquire, render_inverted);
+
+            impl_lock_named!(|$this| $acquire, render_named_escaped);
+            impl_lock_named!(|$this| $acquire, render_named_unescaped);
+            impl_lock_named!(|$this| $acquire, render_named_section);
+            impl_lock_named!(|$this| $acquire, render_named_inverted);
+        }
+    };
+}
+
+macro_rules! impl_lock_method {
+    ( |$this:ident| $acquire:expr, $method:ident ) => {
+        #[inline]
+        fn $method(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+            let $this = self;
+            let guard = $acquire?;
+            guard.$method(context.replace(&&*guard), writer)
+        }
+    };
+}
+
+macro_rules! impl_lock_named {
+    ( |$this:ident| $acquire:expr, $named:ident ) => {
+        #[inline]
+        fn $named(
+            &self,
+            name: &[&str],
+            context: Context,
+            writer: &mut Writer,
+        ) -> Result<bool, Error> {
+            let $this = self;
+            let guard = $acquire?;
+            guard.$named(name, context.replace(&&*guard), writer)
+        }
+    };
+}
+
+impl_lock!(Mutex, |this| acquire(this.try_lock()));
+impl_lock!(RwLock, |this| acquire(this.try_read()));
+impl_lock!(RefCell, |this| borrow(this));
+
+/// Acquire a lock, ignoring poisoning since rendering only reads the value.
+#[inline]
+fn acquire<G>(result: TryLockResult<G>) -> Result<G, Error> {
+    match result {
+        Ok(guard) => Ok(guard),
+        Err(TryLockError::Poisoned(err)) => Ok(err.into_inner()),
+        Err(TryLockError::WouldBlock) => Err(Error::LockUnavailable),
+    }
+}
+
+/// Borrow a cell, which like a lock may already be mutably borrowed elsewhere.
+#[inline]
+fn borrow<T: ?Sized>(cell: &RefCell<T>) -> Result<Ref<'_, T>, Error> {
+    cell.try_borrow().map_err(|_| Error::LockUnavailable)
 }
@@ -1,29 +1,59 @@
+use std::{
+    collections::{
+        BTreeSet,
+        LinkedList,
+        VecDeque,
+    },
+    hash::BuildHasher,
+};
+
 use crate::{
     error::Error,
     render::{
         Context,
+        Iteration,
         Render,
+        Truthiness,
         Writer,
     },
 };
 
 macro_rules! impl_sequence {
     () => {
+        impl_sequence!(|this| this.iter());
+    };
+    ( |$this:ident| $iter:expr ) => {
         #[inline]
-        fn is_truthy(&self) -> bool {
-            !self.is_empty()
+        fn is_truthy(&self, truthiness: &Truthiness) -> bool {
+            truthiness.sequence(self.is_empty())
         }
 
         #[inline]
         fn render_section(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
-            println!("sequence.render_section: {:?}", self);
+            let len = self.len();
+
+            let $this = self;
 
-            for item in self.iter() {
-                item.render_section(context.push(item), writer)?;
+            for (index, item) in $iter.enumerate() {
+                Iteration::new(index, Some(index + 1 == len)).render(item, context, writer)?;
             }
 
             Ok(())
         }
+
+        #[inline]
+        fn lookup(&self, key: &str) -> Option<&dyn Render> {
+            let index = key.parse().ok()?;
+
+            let $this = self;
+
+            $iter.nth(index).map(|item| item as &dyn Render)
+        }
+
+        #[inline]
+        fn length(&self) -> Option<usize> {
+            Some(self.len())
+        }
     };
 }
 
@@ -38,3 +68,103 @@ impl<T: Render> Render for [T] {
 impl<T: Render, const N: usize> Render for [T; N] {
     impl_sequence! {}
 }
+
+impl<T: Render> Render for VecDeque<T> {
+    impl_sequence! {}
+}
+
+impl<T: Render> Render for LinkedList<T> {
+    impl_sequence! {}
+}
+
+// A `HashSet` iterates in sorted order, since its own order is arbitrary and
+// would make the output differ between runs. Sorting is why the impl requires
+// `T: Ord`; a section collects and sorts the items once, and an index lookup
+// selects the single item it needs without sorting the rest.
+#[allow(clippy::disallowed_types)]
+impl<T: Render + Ord, H: BuildHasher> Render for std::collections::HashSet<T, H> {
+    #[inline]
+    fn is_truthy(&self, truthiness: &Truthiness) -> bool {
+        truthiness.sequence(self.is_empty())
+    }
+
+    #[inline]
+    fn render_section(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        let mut items: Vec<_> = self.iter().collect();
+        items.sort_unstable();
+
+        items.render_section(context, writer)
+    }
+
+    #[inline]
+    fn lookup(&self, key: &str) -> Option<&dyn Render> {
+        let index = key.parse().ok().filter(|&index| index < self.len())?;
+
+        let mut items: Vec<_> = self.iter().collect();
+        let (_, item, _) = items.select_nth_unstable(index);
+
+        Some(*item as &dyn Render)
+    }
+
+    #[inline]
+    fn length(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl<T: Render> Render for BTreeSet<T> {
+    impl_sequence! {}
+}
+
+#[cfg(feature = "indexmap")]
+impl<T: Render, H: BuildHasher> Render for indexmap::IndexSet<T, H> {
+    impl_sequence! {}
+}
+
+// Tuples iterate their elements in sections, like a sequence, and an
+// element can be addressed by its position, as in `{{pair.0}}`. Otherwise a
+// tuple behaves like a struct: it's always truthy, has no `length` and renders
+// nothing when interpolated, so the trait defaults cover everything else.
+macro_rules! impl_tuple {
+    ( $( ($($index:tt $ty:ident),+) )* ) => {
+        $(
+            impl<$($ty: Render),+> Render for ($($ty,)+) {
+                #[inline]
+                fn render_section(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+                    let len = [$($index),+].len();
+
+                    $(
+                        Iteration::new($index, Some($index + 1 == len))
+                            .render(&self.$index, context, writer)?;
+                    )+
+
+                    Ok(())
+                }
+
+                // Positional keys, as in `{{pair.0}}`.
+                #[inline]
+                fn lookup(&self, key: &str) -> Option<&dyn Render> {
+                    match key {
+                        $( stringify!($index) => Some(&self.$index), )+
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_tuple! {
+    (0 A)
+    (0 A, 1 B)
+    (0 A, 1 B, 2 C)
+    (0 A, 1 B, 2 C, 3 D)
+    (0 A, 1 B, 2 C, 3 D, 4 E)
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F)
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G)
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H)
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I)
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I, 9 J)
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I, 9 J, 10 K)
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I, 9 J, 10 K, 11 L)
+}
@@ -0,0 +1,1142 @@
+use std::fmt;
+
+use serde::ser::{
+    self,
+    Serialize,
+    SerializeMap,
+    SerializeSeq,
+    SerializeStruct,
+    SerializeStructVariant,
+    SerializeTuple,
+    SerializeTupleStruct,
+    SerializeTupleVariant,
+    Serializer,
+};
+
+use crate::{
+    error::Error,
+    render::{
+        Context,
+        Iteration,
+        Render,
+        Truthiness,
+        Writer,
+    },
+};
+
+/// Render any [`Serialize`] type by walking its serialized form directly,
+/// without first converting it to an intermediate value such as
+/// `serde_json::Value`.
+///
+/// Structs and maps resolve names by key, sequences iterate in sections and
+/// scalars render as they would when serialized to JSON. Unit enum variants
+/// render as their name.
+pub struct Serialized<T>(pub T);
+
+impl<T> fmt::Debug for Serialized<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Serialized").finish_non_exhaustive()
+    }
+}
+
+impl<T: Serialize> Serialized<T> {
+    fn dispatch(
+        &self,
+        method: Method,
+        name: &[&str],
+        context: Context,
+        writer: &mut Writer,
+    ) -> Result<bool, Error> {
+        let dispatch = Dispatch {
+            this: self,
+            method,
+            name,
+            context,
+            writer,
+        };
+
+        self.0
+            .serialize(dispatch)
+            .map_err(|SerializeError(err)| err)
+    }
+}
+
+impl<T: Serialize> Render for Serialized<T> {
+    #[inline]
+    fn is_truthy(&self, truthiness: &Truthiness) -> bool {
+        self.0.serialize(Truthy(truthiness)).unwrap_or(false)
+    }
+
+    #[inline]
+    fn contains_key(&self, key: &str) -> bool {
+        self.0.serialize(HasKey(key)).unwrap_or(false)
+    }
+
+    #[inline]
+    fn render_escaped(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        self.dispatch(Method::Escaped, &[], context, writer)
+            .map(|_| ())
+    }
+
+    #[inline]
+    fn render_unescaped(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        self.dispatch(Method::Unescaped, &[], context, writer)
+            .map(|_| ())
+    }
+
+    #[inline]
+    fn render_section(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        self.dispatch(Method::Section, &[], context, writer)
+            .map(|_| ())
+    }
+
+    #[inline]
+    fn render_inverted(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        self.dispatch(Method::Inverted, &[], context, writer)
+            .map(|_| ())
+    }
+
+    #[inline]
+    fn render_named_escaped(
+        &self,
+        name: &[&str],
+        context: Context,
+        writer: &mut Writer,
+    ) -> Result<bool, Error> {
+        self.dispatch(Method::Escaped, name, context, writer)
+    }
+
+    #[inline]
+    fn render_named_unescaped(
+        &self,
+        name: &[&str],
+        context: Context,
+        writer: &mut Writer,
+    ) -> Result<bool, Error> {
+        self.dispatch(Method::Unescaped, name, context, writer)
+    }
+
+    #[inline]
+    fn render_named_section(
+        &self,
+        name: &[&str],
+        context: Context,
+        writer: &mut Writer,
+    ) -> Result<bool, Error> {
+        self.dispatch(Method::Section, name, context, writer)
+    }
+
+    #[inline]
+    fn render_named_inverted(
+        &self,
+        name: &[&str],
+        context: Context,
+        writer: &mut Writer,
+    ) -> Result<bool, Error> {
+        self.dispatch(Method::Inverted, name, context, writer)
+    }
+}
+
+/// Wraps our error so it can pass through `Serialize` impls.
+#[derive(Debug)]
+struct SerializeError(Error);
+
+impl fmt::Display for SerializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl std::error::Error for SerializeError {}
+
+impl ser::Error for SerializeError {
+    fn custom<M: fmt::Display>(msg: M) -> Self {
+        SerializeError(Error::SerializationFailed(msg.to_string()))
+    }
+}
+
+/// The tag kind being rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Method {
+    Escaped,
+    Unescaped,
+    Section,
+    Inverted,
+}
+
+impl Method {
+    fn render_named(
+        self,
+        value: &dyn Render,
+        name: &[&str],
+        context: Context,
+        writer: &mut Writer,
+    ) -> Result<bool, Error> {
+        match self {
+            Method::Escaped => value.render_named_escaped(name, context, writer),
+            Method::Unescaped => value.render_named_unescaped(name, context, writer),
+            Method::Section => value.render_named_section(name, context, writer),
+            Method::Inverted => value.render_named_inverted(name, context, writer),
+        }
+    }
+}
+
+/// Renders a value as it's serialized, looking up the remaining `name` in
+/// structs and maps. `Ok` is whether the name was found.
+struct Dispatch<'a, 'b, 'w> {
+    /// The `Serialized` value being walked, pushed onto the stack when
+    /// rendering a struct or map section.
+    this: &'a dyn Render,
+    method: Method,
+    name: &'a [&'a str],
+    context: Context<'a>,
+    writer: &'b mut Writer<'w>,
+}
+
+impl<'a, 'b, 'w> Dispatch<'a, 'b, 'w> {
+    /// Render a scalar using its own `Render` impl.
+    fn scalar(self, value: &dyn Render) -> Result<bool, SerializeError> {
+        if !self.name.is_empty() {
+            return Ok(false);
+        }
+
+        self.method
+            .render_named(value, &[], self.context, self.writer)
+            .map_err(SerializeError)
+    }
+
+    /// Push a struct field, map value or sequence element and render the
+    /// remaining name against it.
+    fn nested<T: ?Sized + Serialize>(
+        &mut self,
+        name: &[&str],
+        value: &T,
+    ) -> Result<bool, SerializeError> {
+        let value = Serialized(value);
+
+        self.method
+            .render_named(&value, name, self.context.push(&value), self.writer)
+            .map_err(SerializeError)
+    }
+
+    /// Render a struct or map section once its length is known.
+    fn end_fields(self, len: usize) -> Result<bool, SerializeError> {
+        let truthy = self.context.truthiness().map(len == 0);
+        let result = match self.method {
+            Method::Section if truthy => self.context.push(self.this).render_to_writer(self.writer),
+            Method::Inverted if !truthy => self.context.render_to_writer(self.writer),
+            _ => Ok(()),
+        };
+
+        result.map(|_| true).map_err(SerializeError)
+    }
+
+    fn elements(self, total: Option<usize>) -> Elements<'a, 'b, 'w> {
+        Elements {
+            dispatch: self,
+            total,
+            len: 0,
+            found: false,
+        }
+    }
+
+    fn fields(self) -> Fields<'a, 'b, 'w> {
+        Fields {
+            dispatch: self,
+            len: 0,
+            matched: false,
+            found: false,
+        }
+    }
+}
+
+macro_rules! serialize_scalars {
+    ( $($method:ident : $ty:ty),* ) => {
+        $(
+            #[inline]
+            fn $method(self, value: $ty) -> Result<Self::Ok, Self::Error> {
+                self.scalar(&value)
+            }
+        )*
+    };
+}
+
+impl<'a, 'b, 'w> Serializer for Dispatch<'a, 'b, 'w> {
+    type Ok = bool;
+    type Error = SerializeError;
+    type SerializeSeq = Elements<'a, 'b, 'w>;
+    type SerializeTuple = Elements<'a, 'b, 'w>;
+    type SerializeTupleStruct = Elements<'a, 'b, 'w>;
+    type SerializeTupleVariant = Elements<'a, 'b, 'w>;
+    type SerializeMap = Fields<'a, 'b, 'w>;
+    type SerializeStruct = Fields<'a, 'b, 'w>;
+    type SerializeStructVariant = Fields<'a, 'b, 'w>;
+
+    serialize_scalars!(
+        serialize_bool: bool,
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_i128: i128,
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_u128: u128,
+        serialize_f32: f32,
+        serialize_f64: f64,
+        serialize_str: &str
+    );
+
+    fn serialize_char(self, value: char) -> Result<Self::Ok, Self::Error> {
+        let mut buffer = [0; 4];
+        let value: &str = value.encode_utf8(&mut buffer);
+
+        self.scalar(&value)
+    }
+
+    fn serialize_bytes(self, _value: &[u8]) -> Result<Self::Ok, Self::Error> {
+        self.scalar(&())
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        self.scalar(&())
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        self.scalar(&())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+        self.scalar(&())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.scalar(&variant)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Ok(self.elements(len))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        Ok(self.elements(Some(len)))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Ok(self.elements(Some(len)))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Ok(self.elements(Some(len)))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Ok(self.fields())
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        Ok(self.fields())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Ok(self.fields())
+    }
+}
+
+/// Iterates the elements of a sequence in a section, or counts them for an
+/// inverted section. Numeric keys index into the sequence and `length`
+/// resolves to the number of elements.
+struct Elements<'a, 'b, 'w> {
+    dispatch: Dispatch<'a, 'b, 'w>,
+    /// The number of elements, if the `Serialize` impl provides it. Otherwise
+    /// `@last` can't be resolved in sections.
+    total: Option<usize>,
+    len: usize,
+    found: bool,
+}
+
+impl Elements<'_, '_, '_> {
+    fn element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), SerializeError> {
+        match self.dispatch.name.first() {
+            None if self.dispatch.method == Method::Section => {
+                let last = self.total.map(|total| self.len + 1 == total);
+
+                Iteration::new(self.len, last)
+                    .render(
+                        &Serialized(value),
+                        self.dispatch.context,
+                        self.dispatch.writer,
+                    )
+                    .map_err(SerializeError)?;
+            },
+            Some(key) if !self.found && key.parse() == Ok(self.len) => {
+                self.found = self.dispatch.nested(&self.dispatch.name[1..], value)?;
+            },
+            _ => {},
+        }
+
+        self.len += 1;
+
+        Ok(())
+    }
+
+    fn end(self) -> Result<bool, SerializeError> {
+        let Dispatch {
+            method,
+            name,
+            context,
+            writer,
+            ..
+        } = self.dispatch;
+
+        match name.first() {
+            None => {
+                if method == Method::Inverted && !context.truthiness().sequence(self.len == 0) {
+                    context.render_to_writer(writer).map_err(SerializeError)?;
+                }
+
+                Ok(true)
+            },
+            Some(&"length") => method
+                .render_named(&self.len, &name[1..], context.push(&self.len), writer)
+                .map_err(SerializeError),
+            Some(_) => Ok(self.found),
+        }
+    }
+}
+
+macro_rules! impl_elements {
+    ( $($trait:ident :: $method:ident),* ) => {
+        $(
+            impl $trait for Elements<'_, '_, '_> {
+                type Ok = bool;
+                type Error = SerializeError;
+
+                fn $method<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+                    self.element(value)
+                }
+
+                fn end(self) -> Result<Self::Ok, Self::Error> {
+                    Elements::end(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_elements!(
+    SerializeSeq::serialize_element,
+    SerializeTuple::serialize_element,
+    SerializeTupleStruct::serialize_field,
+    SerializeTupleVariant::serialize_field
+);
+
+/// Looks up the head of the name in the fields of a struct or the entries of
+/// a map, or counts them when rendering the struct or map itself.
+struct Fields<'a, 'b, 'w> {
+    dispatch: Dispatch<'a, 'b, 'w>,
+    len: usize,
+    /// Whether the last map key matched the head of the name.
+    matched: bool,
+    found: bool,
+}
+
+impl Fields<'_, '_, '_> {
+    fn key<K: ?Sized + Serialize>(&mut self, key: &K) -> Result<(), SerializeError> {
+        self.matched = match self.dispatch.name.first() {
+            Some(head) if !self.found => key.serialize(KeyEq(head))?,
+            _ => false,
+        };
+
+        Ok(())
+    }
+
+    fn value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), SerializeError> {
+        self.len += 1;
+
+        if self.matched {
+            self.matched = false;
+            self.found = self.dispatch.nested(&self.dispatch.name[1..], value)?;
+        }
+
+        Ok(())
+    }
+
+    fn field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), SerializeError> {
+        self.matched = !self.found && self.dispatch.name.first() == Some(&key);
+        self.value(value)
+    }
+
+    fn end(self) -> Result<bool, SerializeError> {
+        if self.dispatch.name.is_empty() {
+            self.dispatch.end_fields(self.len)
+        } else {
+            Ok(self.found)
+        }
+    }
+}
+
+impl SerializeMap for Fields<'_, '_, '_> {
+    type Ok = bool;
+    type Error = SerializeError;
+
+    fn serialize_key<K: ?Sized + Serialize>(&mut self, key: &K) -> Result<(), Self::Error> {
+        self.key(key)
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.value(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Fields::end(self)
+    }
+}
+
+macro_rules! impl_fields {
+    ( $($trait:ident),* ) => {
+        $(
+            impl $trait for Fields<'_, '_, '_> {
+                type Ok = bool;
+                type Error = SerializeError;
+
+                fn serialize_field<T: ?Sized + Serialize>(
+                    &mut self,
+                    key: &'static str,
+                    value: &T,
+                ) -> Result<(), Self::Error> {
+                    self.field(key, value)
+                }
+
+                fn end(self) -> Result<Self::Ok, Self::Error> {
+                    Fields::end(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_fields!(SerializeStruct, SerializeStructVariant);
+
+/// Counts the elements of compound values, which are truthy when non-empty
+/// or when `empty` is set.
+struct Count {
+    len: usize,
+    empty: bool,
+}
+
+macro_rules! impl_count {
+    ( $($trait:ident :: $method:ident ( $($arg:ident : $ty:ty),* )),* ) => {
+        $(
+            impl $trait for Count {
+                type Ok = bool;
+                type Error = SerializeError;
+
+                fn $method<T: ?Sized + Serialize>(
+                    &mut self,
+                    $($arg: $ty,)*
+                    _value: &T,
+                ) -> Result<(), Self::Error> {
+                    self.len += 1;
+                    Ok(())
+                }
+
+                fn end(self) -> Result<Self::Ok, Self::Error> {
+                    Ok(self.len > 0 || self.empty)
+                }
+            }
+        )*
+    };
+}
+
+impl_count!(
+    SerializeSeq::serialize_element(),
+    SerializeTuple::serialize_element(),
+    SerializeTupleStruct::serialize_field(),
+    SerializeTupleVariant::serialize_field(),
+    SerializeStruct::serialize_field(_key: &'static str),
+    SerializeStructVariant::serialize_field(_key: &'static str)
+);
+
+impl SerializeMap for Count {
+    type Ok = bool;
+    type Error = SerializeError;
+
+    fn serialize_key<K: ?Sized + Serialize>(&mut self, _key: &K) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, _value: &T) -> Result<(), Self::Error> {
+        self.len += 1;
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(self.len > 0 || self.empty)
+    }
+}
+
+/// Implements the methods shared by `Truthy` and `KeyEq`, whose compound
+/// values are only ever counted. Both provide a `count(is_map)` method.
+macro_rules! impl_compound_count {
+    () => {
+        type SerializeSeq = Count;
+        type SerializeTuple = Count;
+        type SerializeTupleStruct = Count;
+        type SerializeTupleVariant = Count;
+        type SerializeMap = Count;
+        type SerializeStruct = Count;
+        type SerializeStructVariant = Count;
+
+        fn serialize_seq(self, _len: Option<usize>) -> Result<Count, Self::Error> {
+            Ok(self.count(false))
+        }
+
+        fn serialize_tuple(self, _len: usize) -> Result<Count, Self::Error> {
+            Ok(self.count(false))
+        }
+
+        fn serialize_tuple_struct(
+            self,
+            _name: &'static str,
+            _len: usize,
+        ) -> Result<Count, Self::Error> {
+            Ok(self.count(false))
+        }
+
+        fn serialize_tuple_variant(
+            self,
+            _name: &'static str,
+            _index: u32,
+            _variant: &'static str,
+            _len: usize,
+        ) -> Result<Count, Self::Error> {
+            Ok(self.count(false))
+        }
+
+        fn serialize_map(self, _len: Option<usize>) -> Result<Count, Self::Error> {
+            Ok(self.count(true))
+        }
+
+        fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Count, Self::Error> {
+            Ok(self.count(true))
+        }
+
+        fn serialize_struct_variant(
+            self,
+            _name: &'static str,
+            _index: u32,
+            _variant: &'static str,
+            _len: usize,
+        ) -> Result<Count, Self::Error> {
+            Ok(self.count(true))
+        }
+    };
+}
+
+macro_rules! truthy_scalars {
+    ( $($method:ident : $ty:ty),* ) => {
+        $(
+            #[inline]
+            fn $method(self, value: $ty) -> Result<bool, Self::Error> {
+                Ok(value.is_truthy(self.0))
+            }
+        )*
+    };
+}
+
+/// Determines truthiness with the same semantics as the `serde_json::Value`
+/// impl: null is falsey, as are zero, empty strings and empty compounds
+/// unless the policy says otherwise.
+#[derive(Clone, Copy)]
+struct Truthy<'a>(&'a Truthiness);
+
+impl Truthy<'_> {
+    #[inline]
+    fn count(&self, is_map: bool) -> Count {
+        let empty = if is_map {
+            self.0.map(true)
+        } else {
+            self.0.sequence(true)
+        };
+
+        Count { len: 0, empty }
+    }
+}
+
+impl Serializer for Truthy<'_> {
+    type Ok = bool;
+    type Error = SerializeError;
+
+    impl_compound_count!();
+
+    truthy_scalars!(
+        serialize_bool: bool,
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_i128: i128,
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_u128: u128,
+        serialize_f32: f32,
+        serialize_f64: f64,
+        serialize_str: &str
+    );
+
+    fn serialize_char(self, _value: char) -> Result<bool, Self::Error> {
+        Ok(true)
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<bool, Self::Error> {
+        Ok(!value.is_empty())
+    }
+
+    fn serialize_none(self) -> Result<bool, Self::Error> {
+        Ok(false)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<bool, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<bool, Self::Error> {
+        Ok(false)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<bool, Self::Error> {
+        Ok(false)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+    ) -> Result<bool, Self::Error> {
+        Ok(true)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<bool, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<bool, Self::Error> {
+        value.serialize(self)
+    }
+}
+
+macro_rules! key_eq_integers {
+    ( $($method:ident : $ty:ty),* ) => {
+        $(
+            #[inline]
+            fn $method(self, value: $ty) -> Result<bool, Self::Error> {
+                Ok(self.0.parse::<$ty>() == Ok(value))
+            }
+        )*
+    };
+}
+
+/// Compares a serialized map key to a name, without allocating. Integer
+/// and boolean keys match their textual form.
+struct KeyEq<'a>(&'a str);
+
+impl KeyEq<'_> {
+    #[inline]
+    fn count(&self, _is_map: bool) -> Count {
+        Count {
+            len: 0,
+            empty: false,
+        }
+    }
+}
+
+impl Serializer for KeyEq<'_> {
+    type Ok = bool;
+    type Error = SerializeError;
+
+    impl_compound_count!();
+
+    key_eq_integers!(
+        serialize_bool: bool,
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_i128: i128,
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_u128: u128
+    );
+
+    fn serialize_f32(self, _value: f32) -> Result<bool, Self::Error> {
+        Ok(false)
+    }
+
+    fn serialize_f64(self, _value: f64) -> Result<bool, Self::Error> {
+        Ok(false)
+    }
+
+    fn serialize_char(self, value: char) -> Result<bool, Self::Error> {
+        Ok(self.0.parse() == Ok(value))
+    }
+
+    fn serialize_str(self, value: &str) -> Result<bool, Self::Error> {
+        Ok(self.0 == value)
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<bool, Self::Error> {
+        Ok(self.0.as_bytes() == value)
+    }
+
+    fn serialize_none(self) -> Result<bool, Self::Error> {
+        Ok(false)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<bool, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<bool, Self::Error> {
+        Ok(false)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<bool, Self::Error> {
+        Ok(false)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<bool, Self::Error> {
+        Ok(self.0 == variant)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<bool, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<bool, Self::Error> {
+        Ok(false)
+    }
+}
+
+macro_rules! has_key_scalars {
+    ( $($method:ident : $ty:ty),* ) => {
+        $(
+            #[inline]
+            fn $method(self, _value: $ty) -> Result<bool, Self::Error> {
+                Ok(false)
+            }
+        )*
+    };
+}
+
+/// Determines whether a value resolves a key, as `Dispatch` would look it up,
+/// from the field names, map keys or number of elements alone. Values are
+/// never serialized.
+struct HasKey<'a>(&'a str);
+
+impl<'a> HasKey<'a> {
+    #[inline]
+    fn keys(self, sequence: bool) -> Keys<'a> {
+        Keys {
+            key: self.0,
+            sequence,
+            len: 0,
+            found: false,
+        }
+    }
+}
+
+impl<'a> Serializer for HasKey<'a> {
+    type Ok = bool;
+    type Error = SerializeError;
+    type SerializeSeq = Keys<'a>;
+    type SerializeTuple = Keys<'a>;
+    type SerializeTupleStruct = Keys<'a>;
+    type SerializeTupleVariant = Keys<'a>;
+    type SerializeMap = Keys<'a>;
+    type SerializeStruct = Keys<'a>;
+    type SerializeStructVariant = Keys<'a>;
+
+    has_key_scalars!(
+        serialize_bool: bool,
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_i128: i128,
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_u128: u128,
+        serialize_f32: f32,
+        serialize_f64: f64,
+        serialize_char: char,
+        serialize_str: &str,
+        serialize_bytes: &[u8]
+    );
+
+    fn serialize_none(self) -> Result<bool, Self::Error> {
+        Ok(false)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<bool, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<bool, Self::Error> {
+        Ok(false)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<bool, Self::Error> {
+        Ok(false)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+    ) -> Result<bool, Self::Error> {
+        Ok(false)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<bool, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<bool, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Keys<'a>, Self::Error> {
+        Ok(self.keys(true))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Keys<'a>, Self::Error> {
+        Ok(self.keys(true))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Keys<'a>, Self::Error> {
+        Ok(self.keys(true))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Keys<'a>, Self::Error> {
+        Ok(self.keys(true))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Keys<'a>, Self::Error> {
+        Ok(self.keys(false))
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Keys<'a>, Self::Error> {
+        Ok(self.keys(false))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Keys<'a>, Self::Error> {
+        Ok(self.keys(false))
+    }
+}
+
+/// Matches a key against the field names or map keys of a compound value,
+/// or against the indices and `length` of a sequence.
+struct Keys<'a> {
+    key: &'a str,
+    sequence: bool,
+    len: usize,
+    found: bool,
+}
+
+impl Keys<'_> {
+    fn end(self) -> bool {
+        if self.sequence {
+            self.key == "length"
+                || self
+                    .key
+                    .parse::<usize>()
+                    .is_ok_and(|index| index < self.len)
+        } else {
+            self.found
+        }
+    }
+}
+
+macro_rules! impl_keys {
+    ( $($trait:ident :: $method:ident),* ) => {
+        $(
+            impl $trait for Keys<'_> {
+                type Ok = bool;
+                type Error = SerializeError;
+
+                fn $method<T: ?Sized + Serialize>(&mut self, _value: &T) -> Result<(), Self::Error> {
+                    self.len += 1;
+                    Ok(())
+                }
+
+                fn end(self) -> Result<Self::Ok, Self::Error> {
+                    Ok(Keys::end(self))
+                }
+            }
+        )*
+    };
+    ( $($trait:ident),* ; fields ) => {
+        $(
+            impl $trait for Keys<'_> {
+                type Ok = bool;
+                type Error = SerializeError;
+
+                fn serialize_field<T: ?Sized + Serialize>(
+                    &mut self,
+                    key: &'static str,
+                    _value: &T,
+                ) -> Result<(), Self::Error> {
+                    self.found |= self.key == key;
+                    Ok(())
+                }
+
+                fn end(self) -> Result<Self::Ok, Self::Error> {
+                    Ok(Keys::end(self))
+                }
+            }
+        )*
+    };
+}
+
+impl_keys!(
+    SerializeSeq::serialize_element,
+    SerializeTuple::serialize_element,
+    SerializeTupleStruct::serialize_field,
+    SerializeTupleVariant::serialize_field
+);
+
+impl_keys!(SerializeStruct, SerializeStructVariant; fields);
+
+impl SerializeMap for Keys<'_> {
+    type Ok = bool;
+    type Error = SerializeError;
+
+    fn serialize_key<K: ?Sized + Serialize>(&mut self, key: &K) -> Result<(), Self::Error> {
+        if !self.found {
+            self.found = key.serialize(KeyEq(self.key))?;
+        }
+
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, _value: &T) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Keys::end(self))
+    }
+}
@@ -5,11 +5,31 @@ use crate::{
     render::{
         Context,
         Render,
+        Truthiness,
         Writer,
     },
     Template,
 };
 
+// Dotted names resolve through a present value, as in `{{user.name}}`.
+macro_rules! impl_sum_named {
+    ( $pattern:ident, $named:ident, $method:ident ) => {
+        #[inline]
+        fn $named(
+            &self,
+            name: &[&str],
+            context: Context,
+            writer: &mut Writer,
+        ) -> Result<bool, Error> {
+            match self {
+                _ if name.is_empty() => self.$method(context, writer).map(|_| true),
+                $pattern(inner) => inner.$named(name, context, writer),
+                _ => Ok(false),
+            }
+        }
+    };
+}
+
 impl<T: Render> Render for Option<T> {
     #[inline]
     fn size_hint(&self, template: &Template) -> usize {
@@ -20,7 +40,7 @@ impl<T: Render> Render for Option<T> {
     }
 
     #[inline]
-    fn is_truthy(&self) -> bool {
+    fn is_truthy(&self, _truthiness: &Truthiness) -> bool {
         self.is_some()
     }
 
@@ -50,8 +70,30 @@ impl<T: Render> Render for Option<T> {
 
         Ok(())
     }
+
+    #[inline]
+    fn lookup(&self, key: &str) -> Option<&dyn Render> {
+        self.as_ref().and_then(|inner| inner.lookup(key))
+    }
+
+    #[inline]
+    fn length(&self) -> Option<usize> {
+        self.as_ref().and_then(|inner| inner.length())
+    }
+
+    #[inline]
+    fn contains_key(&self, key: &str) -> bool {
+        self.as_ref().is_some_and(|inner| inner.contains_key(key))
+    }
+
+    impl_sum_named!(Some, render_named_escaped, render_escaped);
+    impl_sum_named!(Some, render_named_unescaped, render_unescaped);
+    impl_sum_named!(Some, render_named_section, render_section);
+    impl_sum_named!(Some, render_named_inverted, render_inverted);
 }
 
+/// An `Ok` renders its value. An `Err` is falsey, and its `Debug` form is pushed
+/// for inverted sections so it can be rendered with `{{^result}}{{.}}{{/result}}`.
 impl<T: Render, E: fmt::Debug> Render for Result<T, E> {
     #[inline]
     fn size_hint(&self, template: &Template) -> usize {
@@ -62,8 +104,11 @@ impl<T: Render, E: fmt::Debug> Render for Result<T, E> {
     }
 
     #[inline]
-    fn is_truthy(&self) -> bool {
-        self.is_ok()
+    fn is_truthy(&self, truthiness: &Truthiness) -> bool {
+        match self {
+            Ok(inner) => inner.is_truthy(truthiness),
+            Err(_) => false,
+        }
     }
 
     #[inline]
@@ -92,4 +137,48 @@ impl<T: Render, E: fmt::Debug> Render for Result<T, E> {
 
         Ok(())
     }
+
+    #[inline]
+    fn render_inverted(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        match self {
+            Ok(inner) => inner.render_inverted(context, writer),
+            Err(err) => context.push(&Message(err)).render_to_writer(writer),
+        }
+    }
+
+    #[inline]
+    fn lookup(&self, key: &str) -> Option<&dyn Render> {
+        self.as_ref().ok().and_then(|inner| inner.lookup(key))
+    }
+
+    #[inline]
+    fn length(&self) -> Option<usize> {
+        self.as_ref().ok().and_then(|inner| inner.length())
+    }
+
+    #[inline]
+    fn contains_key(&self, key: &str) -> bool {
+        self.as_ref().is_ok_and(|inner| inner.contains_key(key))
+    }
+
+    impl_sum_named!(Ok, render_named_escaped, render_escaped);
+    impl_sum_named!(Ok, render_named_unescaped, render_unescaped);
+    impl_sum_named!(Ok, render_named_section, render_section);
+    impl_sum_named!(Ok, render_named_inverted, render_inverted);
+}
+
+/// The `Debug` form of an error.
+#[derive(Debug)]
+struct Message<'a, E>(&'a E);
+
+impl<E: fmt::Debug> Render for Message<'_, E> {
+    #[inline]
+    fn render_escaped(&self, _context: Context, writer: &mut Writer) -> Result<(), Error> {
+        writer.format_escaped(format_args!("{:?}", self.0))
+    }
+
+    #[inline]
+    fn render_unescaped(&self, _context: Context, writer: &mut Writer) -> Result<(), Error> {
+        writer.format_unescaped(format_args!("{:?}", self.0))
+    }
 }
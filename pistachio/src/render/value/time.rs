@@ -0,0 +1,83 @@
+use std::fmt;
+
+use time::{
+    format_description::{
+        well_known::Rfc3339,
+        BorrowedFormatItem,
+    },
+    formatting::Formattable,
+    macros::format_description,
+    Date,
+    OffsetDateTime,
+    PrimitiveDateTime,
+    Time,
+};
+
+use super::formatted::Formatted;
+use crate::{
+    error::Error,
+    render::{
+        Context,
+        Render,
+        Writer,
+    },
+    Template,
+};
+
+const DATE: &[BorrowedFormatItem<'_>] = format_description!("[year]-[month]-[day]");
+const DATE_TIME: &[BorrowedFormatItem<'_>] =
+    format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]");
+const TIME: &[BorrowedFormatItem<'_>] = format_description!("[hour]:[minute]:[second]");
+
+macro_rules! impl_time {
+    ( $($ty:ty : $format:expr),* ) => {
+        $(
+            impl Render for $ty {
+                #[inline]
+                fn size_hint(&self, _template: &Template) -> usize {
+                    25
+                }
+
+                #[inline]
+                fn render_escaped(
+                    &self,
+                    _context: Context,
+                    writer: &mut Writer,
+                ) -> Result<(), Error> {
+                    render_with(writer, self.format(&$format))
+                }
+            }
+
+            impl<F: Formattable + fmt::Debug + ?Sized> Render for Formatted<$ty, &F> {
+                #[inline]
+                fn render_escaped(
+                    &self,
+                    _context: Context,
+                    writer: &mut Writer,
+                ) -> Result<(), Error> {
+                    render_with(writer, self.0.format(self.1))
+                }
+            }
+        )*
+    };
+}
+
+impl_time!(
+    OffsetDateTime: Rfc3339,
+    PrimitiveDateTime: DATE_TIME,
+    Date: DATE,
+    Time: TIME
+);
+
+/// Write the result of formatting, where formatting fails if the format
+/// requires components the value doesn't have, such as an offset for a `Date`.
+#[inline]
+fn render_with(
+    writer: &mut Writer,
+    text: Result<String, time::error::Format>,
+) -> Result<(), Error> {
+    match text {
+        Ok(text) => writer.write_escaped(&text),
+        Err(err) => Err(Error::InvalidFormat(err.to_string())),
+    }
+}
@@ -0,0 +1,141 @@
+use toml::{
+    value::Datetime,
+    Table,
+    Value,
+};
+
+use super::map::impl_map;
+use crate::{
+    error::Error,
+    render::{
+        Context,
+        Render,
+        Truthiness,
+        Writer,
+    },
+    Template,
+};
+
+impl Render for Value {
+    #[inline]
+    fn size_hint(&self, template: &Template) -> usize {
+        match self {
+            Value::String(s) => s.size_hint(template),
+            Value::Integer(i) => i.size_hint(template),
+            Value::Float(f) => f.size_hint(template),
+            Value::Boolean(b) => b.size_hint(template),
+            Value::Datetime(d) => d.size_hint(template),
+            Value::Array(v) => v.size_hint(template),
+            Value::Table(m) => m.size_hint(template),
+        }
+    }
+
+    #[inline]
+    fn is_truthy(&self, truthiness: &Truthiness) -> bool {
+        match self {
+            Value::String(s) => s.is_truthy(truthiness),
+            Value::Integer(i) => i.is_truthy(truthiness),
+            Value::Float(f) => f.is_truthy(truthiness),
+            Value::Boolean(b) => b.is_truthy(truthiness),
+            Value::Datetime(d) => d.is_truthy(truthiness),
+            Value::Array(v) => v.is_truthy(truthiness),
+            Value::Table(m) => m.is_truthy(truthiness),
+        }
+    }
+
+    #[inline]
+    fn lookup(&self, key: &str) -> Option<&dyn Render> {
+        match self {
+            Value::Array(v) => v.lookup(key),
+            Value::Table(m) => m.lookup(key),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn length(&self) -> Option<usize> {
+        match self {
+            Value::Array(v) => v.length(),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn render_escaped(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        match self {
+            Value::String(s) => s.render_escaped(context, writer),
+            Value::Integer(i) => i.render_escaped(context, writer),
+            Value::Float(f) => f.render_escaped(context, writer),
+            Value::Boolean(b) => b.render_escaped(context, writer),
+            Value::Datetime(d) => d.render_escaped(context, writer),
+            Value::Array(v) => v.render_escaped(context, writer),
+            Value::Table(m) => m.render_escaped(context, writer),
+        }
+    }
+
+    #[inline]
+    fn render_unescaped(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        match self {
+            Value::String(s) => s.render_unescaped(context, writer),
+            Value::Integer(i) => i.render_unescaped(context, writer),
+            Value::Float(f) => f.render_unescaped(context, writer),
+            Value::Boolean(b) => b.render_unescaped(context, writer),
+            Value::Datetime(d) => d.render_unescaped(context, writer),
+            Value::Array(v) => v.render_unescaped(context, writer),
+            Value::Table(m) => m.render_unescaped(context, writer),
+        }
+    }
+
+    #[inline]
+    fn render_section(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        match self {
+            Value::String(s) => s.render_section(context, writer),
+            Value::Integer(i) => i.render_section(context, writer),
+            Value::Float(f) => f.render_section(context, writer),
+            Value::Boolean(b) => b.render_section(context, writer),
+            Value::Datetime(d) => d.render_section(context, writer),
+            Value::Array(v) => v.render_section(context, writer),
+            Value::Table(m) => m.render_section(context, writer),
+        }
+    }
+
+    #[inline]
+    fn render_inverted(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        match self {
+            Value::String(s) => s.render_inverted(context, writer),
+            Value::Integer(i) => i.render_inverted(context, writer),
+            Value::Float(f) => f.render_inverted(context, writer),
+            Value::Boolean(b) => b.render_inverted(context, writer),
+            Value::Datetime(d) => d.render_inverted(context, writer),
+            Value::Array(v) => v.render_inverted(context, writer),
+            Value::Table(m) => m.render_inverted(context, writer),
+        }
+    }
+}
+
+impl Render for Table {
+    impl_map! {}
+}
+
+// Dates and times render in their RFC 3339 form, as they appear in TOML.
+impl Render for Datetime {
+    #[inline]
+    fn size_hint(&self, _template: &Template) -> usize {
+        25
+    }
+
+    #[inline]
+    fn render_escaped(&self, _context: Context, writer: &mut Writer) -> Result<(), Error> {
+        writer.format_escaped(self)
+    }
+
+    #[inline]
+    fn render_unescaped(&self, _context: Context, writer: &mut Writer) -> Result<(), Error> {
+        writer.format_unescaped(self)
+    }
+
+    #[inline]
+    fn render_section(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        context.render_to_writer(writer)
+    }
+}
@@ -0,0 +1,124 @@
+/// The rules deciding which values are falsey, and so skip `{{#section}}`
+/// and render `{{^inverted}}` tags. Configured with
+/// [`Builder::truthiness`](crate::Builder::truthiness).
+///
+/// By default `false`, `null`, zero, empty strings, empty sequences and empty
+/// maps are falsey, and floats within `EPSILON` of zero count as zero.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Truthiness {
+    empty_string: bool,
+    zero_string: bool,
+    zero: bool,
+    approximate_zero: bool,
+    empty_sequence: bool,
+    empty_map: bool,
+}
+
+pub(crate) static DEFAULT_TRUTHINESS: Truthiness = Truthiness::new();
+
+impl Default for Truthiness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Truthiness {
+    pub const fn new() -> Self {
+        Self {
+            empty_string: false,
+            zero_string: true,
+            zero: false,
+            approximate_zero: true,
+            empty_sequence: false,
+            empty_map: false,
+        }
+    }
+
+    /// The semantics of `mustache.js`, where empty maps are truthy and only
+    /// exactly zero or `NaN` floats are falsey.
+    pub const fn javascript() -> Self {
+        Self {
+            empty_map: true,
+            approximate_zero: false,
+            ..Self::new()
+        }
+    }
+
+    /// Whether `""` is truthy.
+    pub fn empty_string(mut self, truthy: bool) -> Self {
+        self.empty_string = truthy;
+        self
+    }
+
+    /// Whether the string `"0"` is truthy, as it is in JavaScript but not PHP.
+    pub fn zero_string(mut self, truthy: bool) -> Self {
+        self.zero_string = truthy;
+        self
+    }
+
+    /// Whether zero numbers are truthy.
+    pub fn zero(mut self, truthy: bool) -> Self {
+        self.zero = truthy;
+        self
+    }
+
+    /// Whether floats within `EPSILON` of zero are treated as zero, rather
+    /// than only `0.0` itself.
+    pub fn approximate_zero(mut self, approximate: bool) -> Self {
+        self.approximate_zero = approximate;
+        self
+    }
+
+    /// Whether empty sequences are truthy. Sections still render once per
+    /// element, so only `{{^inverted}}` tags are affected.
+    pub fn empty_sequence(mut self, truthy: bool) -> Self {
+        self.empty_sequence = truthy;
+        self
+    }
+
+    /// Whether empty maps are truthy.
+    pub fn empty_map(mut self, truthy: bool) -> Self {
+        self.empty_map = truthy;
+        self
+    }
+
+    /// Whether a string is truthy.
+    #[inline]
+    pub fn string(&self, value: &str) -> bool {
+        match value {
+            "" => self.empty_string,
+            "0" => self.zero_string,
+            _ => true,
+        }
+    }
+
+    /// Whether a number that is or isn't zero is truthy.
+    #[inline]
+    pub fn number(&self, is_zero: bool) -> bool {
+        !is_zero || self.zero
+    }
+
+    /// Whether a float is truthy. `NaN` is always falsey.
+    #[inline]
+    pub fn float(&self, value: f64, epsilon: f64) -> bool {
+        if value.is_nan() {
+            false
+        } else if self.approximate_zero {
+            self.number(value.abs() <= epsilon)
+        } else {
+            self.number(value == 0.0)
+        }
+    }
+
+    /// Whether a sequence that is or isn't empty is truthy.
+    #[inline]
+    pub fn sequence(&self, is_empty: bool) -> bool {
+        !is_empty || self.empty_sequence
+    }
+
+    /// Whether a map that is or isn't empty is truthy.
+    #[inline]
+    pub fn map(&self, is_empty: bool) -> bool {
+        !is_empty || self.empty_map
+    }
+}
@@ -0,0 +1,28 @@
+use url::Url;
+
+use crate::{
+    error::Error,
+    render::{
+        Context,
+        Render,
+        Writer,
+    },
+    Template,
+};
+
+impl Render for Url {
+    #[inline]
+    fn size_hint(&self, _template: &Template) -> usize {
+        self.as_str().len()
+    }
+
+    #[inline]
+    fn render_escaped(&self, _context: Context, writer: &mut Writer) -> Result<(), Error> {
+        writer.write_escaped(self.as_str())
+    }
+
+    #[inline]
+    fn render_unescaped(&self, _context: Context, writer: &mut Writer) -> Result<(), Error> {
+        writer.write_unescaped(self.as_str())
+    }
+}
@@ -0,0 +1,45 @@
+use uuid::{
+    fmt::{
+        Braced,
+        Hyphenated,
+        Simple,
+        Urn,
+    },
+    Uuid,
+};
+
+use crate::{
+    error::Error,
+    render::{
+        Context,
+        Render,
+        Writer,
+    },
+    Template,
+};
+
+// A `Uuid` renders hyphenated, and other formats can be chosen with its
+// adapters, as in `uuid.simple()`.
+macro_rules! impl_uuid {
+    ( $($ty:ty : $len:literal),* ) => {
+        $(
+            impl Render for $ty {
+                #[inline]
+                fn size_hint(&self, _template: &Template) -> usize {
+                    $len
+                }
+
+                #[inline]
+                fn render_escaped(
+                    &self,
+                    _context: Context,
+                    writer: &mut Writer,
+                ) -> Result<(), Error> {
+                    writer.format_unescaped(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_uuid!(Uuid: 36, Hyphenated: 36, Simple: 32, Urn: 45, Braced: 38);
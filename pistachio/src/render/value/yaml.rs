@@ -0,0 +1,168 @@
+use serde_yaml::{
+    Mapping,
+    Number,
+    Value,
+};
+
+use super::map::impl_map;
+use crate::{
+    error::Error,
+    render::{
+        Context,
+        Render,
+        Truthiness,
+        Writer,
+    },
+    Template,
+};
+
+// Tagged values, such as `!Thing value`, render as their untagged value.
+impl Render for Value {
+    #[inline]
+    fn size_hint(&self, template: &Template) -> usize {
+        match self {
+            Value::Null => ().size_hint(template),
+            Value::Bool(b) => b.size_hint(template),
+            Value::Number(n) => n.size_hint(template),
+            Value::String(s) => s.size_hint(template),
+            Value::Sequence(v) => v.size_hint(template),
+            Value::Mapping(m) => m.size_hint(template),
+            Value::Tagged(t) => t.value.size_hint(template),
+        }
+    }
+
+    #[inline]
+    fn is_truthy(&self, truthiness: &Truthiness) -> bool {
+        match self {
+            Value::Null => ().is_truthy(truthiness),
+            Value::Bool(b) => b.is_truthy(truthiness),
+            Value::Number(n) => n.is_truthy(truthiness),
+            Value::String(s) => s.is_truthy(truthiness),
+            Value::Sequence(v) => v.is_truthy(truthiness),
+            Value::Mapping(m) => m.is_truthy(truthiness),
+            Value::Tagged(t) => t.value.is_truthy(truthiness),
+        }
+    }
+
+    #[inline]
+    fn lookup(&self, key: &str) -> Option<&dyn Render> {
+        match self {
+            Value::Sequence(v) => v.lookup(key),
+            Value::Mapping(m) => m.lookup(key),
+            Value::Tagged(t) => t.value.lookup(key),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn length(&self) -> Option<usize> {
+        match self {
+            Value::Sequence(v) => v.length(),
+            Value::Tagged(t) => t.value.length(),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn render_escaped(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        match self {
+            Value::Null => ().render_escaped(context, writer),
+            Value::Bool(b) => b.render_escaped(context, writer),
+            Value::Number(n) => n.render_escaped(context, writer),
+            Value::String(s) => s.render_escaped(context, writer),
+            Value::Sequence(v) => v.render_escaped(context, writer),
+            Value::Mapping(m) => m.render_escaped(context, writer),
+            Value::Tagged(t) => t.value.render_escaped(context, writer),
+        }
+    }
+
+    #[inline]
+    fn render_unescaped(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        match self {
+            Value::Null => ().render_unescaped(context, writer),
+            Value::Bool(b) => b.render_unescaped(context, writer),
+            Value::Number(n) => n.render_unescaped(context, writer),
+            Value::String(s) => s.render_unescaped(context, writer),
+            Value::Sequence(v) => v.render_unescaped(context, writer),
+            Value::Mapping(m) => m.render_unescaped(context, writer),
+            Value::Tagged(t) => t.value.render_unescaped(context, writer),
+        }
+    }
+
+    #[inline]
+    fn render_section(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        match self {
+            Value::Null => ().render_section(context, writer),
+            Value::Bool(b) => b.render_section(context, writer),
+            Value::Number(n) => n.render_section(context, writer),
+            Value::String(s) => s.render_section(context, writer),
+            Value::Sequence(v) => v.render_section(context, writer),
+            Value::Mapping(m) => m.render_section(context, writer),
+            Value::Tagged(t) => t.value.render_section(context, writer),
+        }
+    }
+
+    #[inline]
+    fn render_inverted(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        match self {
+            Value::Null => ().render_inverted(context, writer),
+            Value::Bool(b) => b.render_inverted(context, writer),
+            Value::Number(n) => n.render_inverted(context, writer),
+            Value::String(s) => s.render_inverted(context, writer),
+            Value::Sequence(v) => v.render_inverted(context, writer),
+            Value::Mapping(m) => m.render_inverted(context, writer),
+            Value::Tagged(t) => t.value.render_inverted(context, writer),
+        }
+    }
+}
+
+impl Render for Mapping {
+    impl_map! {}
+}
+
+/// Apply `f` to the number as a [`Render`], as an integer where it is one
+/// so integers are rendered exactly, rather than via `f64`.
+#[inline]
+fn with_number<R>(number: &Number, f: impl FnOnce(&dyn Render) -> R) -> R {
+    if let Some(n) = number.as_u64() {
+        f(&n)
+    } else if let Some(n) = number.as_i64() {
+        f(&n)
+    } else if let Some(n) = number.as_f64() {
+        f(&n)
+    } else {
+        f(&())
+    }
+}
+
+impl Render for Number {
+    #[inline]
+    fn size_hint(&self, template: &Template) -> usize {
+        with_number(self, |n| n.size_hint(template))
+    }
+
+    #[inline]
+    fn is_truthy(&self, truthiness: &Truthiness) -> bool {
+        with_number(self, |n| n.is_truthy(truthiness))
+    }
+
+    #[inline]
+    fn render_escaped(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        with_number(self, |n| n.render_escaped(context, writer))
+    }
+
+    #[inline]
+    fn render_unescaped(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        with_number(self, |n| n.render_unescaped(context, writer))
+    }
+
+    #[inline]
+    fn render_section(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        with_number(self, |n| n.render_section(context, writer))
+    }
+
+    #[inline]
+    fn render_inverted(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+        with_number(self, |n| n.render_inverted(context, writer))
+    }
+}
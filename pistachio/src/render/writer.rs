@@ -7,12 +7,21 @@ use crate::error::Error;
 
 pub struct Writer<'a> {
     inner: &'a mut dyn io::Write,
+    /// The accumulated indentation of any standalone partials being rendered.
+    indent: String,
+    /// Whether the indentation is owed before the next write, ie. the last
+    /// template content written ended with a newline.
+    pending: bool,
 }
 
 impl<'a> Writer<'a> {
     #[inline]
     pub fn new(inner: &'a mut impl io::Write) -> Self {
-        Self { inner }
+        Self {
+            inner,
+            indent: String::new(),
+            pending: false,
+        }
     }
 
     #[inline]
@@ -23,6 +32,7 @@ impl<'a> Writer<'a> {
 
     #[inline]
     pub fn write_unescaped(&mut self, string: &str) -> Result<(), Error> {
+        self.write_indent().map_err(Error::Io)?;
         self.inner.write_all(string.as_bytes()).map_err(Error::Io)
     }
 
@@ -35,11 +45,66 @@ impl<'a> Writer<'a> {
 
     #[inline]
     pub fn format_unescaped(&mut self, display: impl fmt::Display) -> Result<(), Error> {
+        self.write_indent().map_err(Error::Io)?;
         write!(self.inner, "{}", display).map_err(Error::Io)
     }
 
+    /// Write raw template content, indenting each line if inside a standalone partial.
+    ///
+    /// Unlike interpolated values, which are written verbatim, every line of
+    /// template content is prefixed with the current indentation.
+    #[inline]
+    pub fn write_content(&mut self, text: &str) -> Result<(), Error> {
+        if self.indent.is_empty() {
+            return self.inner.write_all(text.as_bytes()).map_err(Error::Io);
+        }
+
+        for line in text.split_inclusive('\n') {
+            self.write_indent().map_err(Error::Io)?;
+            self.inner.write_all(line.as_bytes()).map_err(Error::Io)?;
+            self.pending = line.ends_with('\n');
+        }
+
+        Ok(())
+    }
+
+    /// Run `action` with `indent` appended to the current indentation.
+    #[inline]
+    pub(crate) fn indented<F>(&mut self, indent: &str, action: F) -> Result<(), Error>
+    where
+        F: FnOnce(&mut Self) -> Result<(), Error>,
+    {
+        if indent.is_empty() {
+            return action(self);
+        }
+
+        let len = self.indent.len();
+
+        // Standalone tags always begin a line.
+        self.indent.push_str(indent);
+        self.pending = true;
+
+        let result = action(self);
+
+        self.indent.truncate(len);
+
+        result
+    }
+
+    #[inline]
+    fn write_indent(&mut self) -> Result<(), io::Error> {
+        if self.pending {
+            self.pending = false;
+            self.inner.write_all(self.indent.as_bytes())?;
+        }
+
+        Ok(())
+    }
+
     #[inline]
     fn write_escaped_bytes(&mut self, bytes: &[u8]) -> Result<(), io::Error> {
+        self.write_indent()?;
+
         let mut start = 0;
 
         for (index, byte) in bytes.iter().enumerate() {
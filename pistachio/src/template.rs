@@ -3,10 +3,17 @@ use std::{
     fmt,
     io,
     iter,
+    sync::{
+        Arc,
+        Weak,
+    },
 };
 
 use crate::{
-    lexer::Lexer,
+    lexer::{
+        Delimiters,
+        Lexer,
+    },
     map,
     parser::{
         Parser,
@@ -14,12 +21,13 @@ use crate::{
     },
     render::{
         Context,
+        NumberFormat,
         Render,
+        Truthiness,
         Writer,
     },
     Error,
     Loader,
-    LoadingDisabled,
 };
 
 /// Represents a parsed and normalised template.
@@ -29,6 +37,9 @@ pub struct Template<'a> {
     nodes: Vec<Node<'a>>,
     source: Cow<'a, str>,
     raise: bool,
+    number_format: NumberFormat,
+    truthiness: Truthiness,
+    loader: Option<Weak<dyn Loader>>,
 }
 
 impl<'a> Template<'a> {
@@ -36,7 +47,31 @@ impl<'a> Template<'a> {
     where
         S: Into<Cow<'a, str>>,
     {
-        Template::with_loader(source.into(), &mut LoadingDisabled)
+        Template::parse(source.into(), false, None, Delimiters::default())
+    }
+
+    /// Parse a template whose `{{>partial}}` and `{{<parent}}` tags are resolved
+    /// at render time by `loader`. Only a weak reference is kept, since loaders
+    /// typically cache the templates they create, so the loader's settings are
+    /// copied into the template now.
+    pub fn with_loader(
+        source: Cow<'a, str>,
+        loader: Weak<dyn Loader>,
+    ) -> Result<Template<'a>, Error> {
+        let (raise, number_format, truthiness) = match loader.upgrade() {
+            Some(loader) => (
+                loader.raise_if_missing(),
+                loader.number_format().clone(),
+                loader.truthiness().clone(),
+            ),
+            None => (false, NumberFormat::new(), Truthiness::new()),
+        };
+
+        let mut template = Template::parse(source, raise, Some(loader), Delimiters::default())?;
+        template.number_format = number_format;
+        template.truthiness = truthiness;
+
+        Ok(template)
     }
 
     pub fn size_hint(&self) -> usize {
@@ -52,31 +87,38 @@ impl<'a> Template<'a> {
     }
 
     #[inline]
-    pub fn with_loader(
+    pub(crate) fn parse(
         source: Cow<'a, str>,
-        loader: &mut dyn Loader<'a>,
+        raise: bool,
+        loader: Option<Weak<dyn Loader>>,
+        delimiters: Delimiters<'a>,
     ) -> Result<Template<'a>, Error> {
-        let raise = loader.raise_if_missing();
         if source.is_empty() {
             return Ok(Template {
                 size_hint: 0,
                 nodes: Vec::new(),
                 source,
                 raise,
+                number_format: NumberFormat::new(),
+                truthiness: Truthiness::new(),
+                loader,
             });
         }
 
         let unsafe_source: &'a str = unsafe { &*(&*source as *const str) };
 
         let mut size_hint = 0;
-        let lexer = Lexer::new(&unsafe_source);
-        let nodes = Parser::new().parse(&mut size_hint, loader, &unsafe_source, lexer)?;
+        let lexer = Lexer::with_delimiters(&unsafe_source, delimiters);
+        let nodes = Parser::new().parse(&mut size_hint, &unsafe_source, lexer)?;
 
         Ok(Template {
             size_hint,
             nodes,
             source,
             raise,
+            number_format: NumberFormat::new(),
+            truthiness: Truthiness::new(),
+            loader,
         })
     }
 
@@ -89,7 +131,17 @@ impl<'a> Template<'a> {
         // Add 25% for escaping and various expansions.
         capacity += capacity / 4;
 
-        Context::new(self.raise, &self.nodes, &vars).render_to_string(capacity)
+        let loader = self.loader();
+
+        Context::new(
+            self.raise,
+            &self.number_format,
+            &self.truthiness,
+            &self.nodes,
+            &vars,
+            loader.as_deref(),
+        )
+        .render_to_string(capacity)
     }
 
     pub fn render_to_writer<T, W>(&self, vars: &T, writer: &mut W) -> Result<(), Error>
@@ -98,58 +150,56 @@ impl<'a> Template<'a> {
         W: io::Write,
     {
         let mut writer = Writer::new(writer);
-
-        Context::new(self.raise, &self.nodes, &vars).render_to_writer(&mut writer)?;
+        let loader = self.loader();
+
+        Context::new(
+            self.raise,
+            &self.number_format,
+            &self.truthiness,
+            &self.nodes,
+            &vars,
+            loader.as_deref(),
+        )
+        .render_to_writer(&mut writer)?;
 
         Ok(())
     }
 
-    // pub(crate) fn include_partial(&self, text: &'a str) -> Vec<Node<'a>> {
-    //     let mut nodes = Vec::with_capacity(self.nodes.len() + 1);
-    //     nodes.push(Node::content(text));
-    //     nodes.extend_from_slice(&self.nodes);
-    //     nodes
-    // }
+    /// Render the template when formatted, such as by `format!` or a logger.
+    ///
+    /// The template is rendered in full before anything is written, and a
+    /// rendering error such as a missing variable is written in place of the
+    /// output, since [`fmt::Display`] can't fail without panicking `format!`.
+    /// Use [`Template::render`] to handle the error instead.
+    pub fn display<'t, T>(&'t self, vars: &'t T) -> impl fmt::Display + 't
+    where
+        T: Render,
+    {
+        Display {
+            template: self,
+            vars,
+        }
+    }
 
-    // pub(crate) fn inherit_parent(
-    //     &self,
-    //     text: &'a str,
-    //     child: Option<Vec<Node<'a>>>,
-    //     close: Node<'a>,
-    // ) -> Vec<Node<'a>> {
-    //     let child = child.unwrap_or_else(|| Vec::new());
-    //     let mut nodes = Vec::with_capacity(self.nodes.len() + 2);
-    //     let mut blocks: map::Map<_, _> = child
-    //         .iter()
-    //         .chain(iter::once(&close))
-    //         .enumerate()
-    //         .filter_map(|(i, node)| match node.tag {
-    //             Tag::Block => Some((node.key, (i, node.children()))),
-    //             _ => None,
-    //         })
-    //         .collect();
-
-    //     nodes.push(Node::content(text));
-
-    //     for node in &self.nodes {
-    //         match node.tag {
-    //             // For each block in the parent replace with any matching block override
-    //             // found in `nodes`. Any blocks that aren't overriden are preserved.
-    //             Tag::Block => {
-    //                 if let Some((index, next)) = blocks.remove(node.key) {
-    //                     nodes.extend_from_slice(&child[index..next as usize]);
-    //                 } else {
-    //                     nodes.push(node.clone());
-    //                 }
-    //             },
-
-    //             // Any non-block tags are preserved.
-    //             _ => nodes.push(node.clone()),
-    //         }
-    //     }
-
-    //     nodes
-    // }
+    #[inline]
+    fn loader(&self) -> Option<Arc<dyn Loader>> {
+        self.loader.as_ref().and_then(Weak::upgrade)
+    }
+}
+
+/// A template and its variables, rendered when formatted.
+struct Display<'t, T> {
+    template: &'t Template<'t>,
+    vars: &'t T,
+}
+
+impl<T: Render> fmt::Display for Display<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.template.render(self.vars) {
+            Ok(text) => f.write_str(&text),
+            Err(err) => write!(f, "{}", err),
+        }
+    }
 }
 
 /// XXX: Tag -> Control, Node -> Tag/Block?
@@ -194,6 +244,18 @@ pub struct Node<'a> {
     pub tag: Tag,
     /// Dotted key identifiers, like `foo.bar.baz`.
     pub name: Name<'a>,
+    /// Whitespace preceeding a standalone `{{>partial}}` tag, which is
+    /// prepended to every line of the included template. Closing tags keep it
+    /// so the raw content of a section can include it.
+    pub indent: &'a str,
+    /// The unparsed content of a `{{#section}}`, which is passed to section lambdas.
+    pub raw: &'a str,
+    /// The delimiters in effect at a `{{#section}}` tag, which are used to
+    /// parse the text returned by section lambdas.
+    pub delimiters: Delimiters<'a>,
+    /// Whether the template named by a `{{>*partial}}` or `{{<*parent}}` tag
+    /// is looked up on the context stack, rather than used verbatim.
+    pub dynamic: bool,
     /// The number of child sub-nodes below to this node.
     children: u32,
 }
@@ -204,6 +266,10 @@ impl<'a> Node<'a> {
             text,
             tag,
             name,
+            indent: "",
+            raw: "",
+            delimiters: Delimiters::default(),
+            dynamic: false,
             children: children as u32,
         }
     }
@@ -216,6 +282,10 @@ impl<'a> Node<'a> {
                 start: 0,
                 keys: vec![],
             },
+            indent: "",
+            raw: "",
+            delimiters: Delimiters::default(),
+            dynamic: false,
             children: 0,
         }
     }
@@ -228,70 +298,95 @@ impl<'a> Node<'a> {
         Self::new(text, Tag::Unescaped, name, 0)
     }
 
-    pub fn closing(text: &'a str, name: Name<'a>) -> Self {
-        Self::new(text, Tag::Closing, name, 0)
+    pub fn closing(text: &'a str, indent: &'a str, name: Name<'a>) -> Self {
+        Self {
+            indent,
+            ..Self::new(text, Tag::Closing, name, 0)
+        }
+    }
+
+    pub fn partial(text: &'a str, indent: &'a str, name: Name<'a>) -> Self {
+        Self {
+            indent,
+            ..Self::new(text, Tag::Partial, name, 0)
+        }
     }
 
     pub fn section(
         text: &'a str,
+        raw: &'a str,
+        delimiters: Delimiters<'a>,
         name: Name<'a>,
         nodes: Option<Vec<Node<'a>>>,
         close: Node<'a>,
     ) -> Vec<Node<'a>> {
-        let children = (nodes.as_deref().map(|nodes| nodes.len()).unwrap_or(0) + 1) as u32;
+        let mut nodes = Self::enclosing(text, Tag::Section, name, nodes, close);
+        nodes[0].raw = raw;
+        nodes[0].delimiters = delimiters;
+        nodes
+    }
 
-        iter::once(Self {
-            text,
-            tag: Tag::Section,
-            name,
-            children,
-        })
-        .chain(nodes.into_iter().flatten())
-        .chain(iter::once(close))
-        .collect()
+    pub fn inverted(
+        text: &'a str,
+        name: Name<'a>,
+        nodes: Option<Vec<Node<'a>>>,
+        close: Node<'a>,
+    ) -> Vec<Node<'a>> {
+        Self::enclosing(text, Tag::Inverted, name, nodes, close)
     }
 
-    // pub fn inverted(
-    //     text: &'a str,
-    //     name: Name<'a>,
-    //     nodes: Option<Vec<Node<'a>>>,
-    //     close: Node<'a>,
-    // ) -> Vec<Node<'a>> {
-    //     name.explode(text, Tag::Inverted, Tag::Inverted, nodes, Some(close))
-    // }
+    /// Flatten a tag and its children, including the closing tag, into the
+    /// contiguous layout expected by [`Context::render_to_writer`].
+    fn enclosing(
+        text: &'a str,
+        tag: Tag,
+        name: Name<'a>,
+        nodes: Option<Vec<Node<'a>>>,
+        close: Node<'a>,
+    ) -> Vec<Node<'a>> {
+        let children = nodes.as_deref().map(|nodes| nodes.len()).unwrap_or(0) + 1;
 
-    // pub fn block(
-    //     text: &'a str,
-    //     name: Name<'a>,
-    //     nodes: Option<Vec<Node<'a>>>,
-    //     close: Node<'a>,
-    // ) -> Vec<Node<'a>> {
-    //     let nodes = nodes.unwrap_or_else(|| Vec::new());
-
-    //     iter::once(Node::new(text, Tag::Block, key, nodes.len() + 1))
-    //         .chain(nodes)
-    //         .chain(iter::once(close))
-    //         .collect()
-    // }
+        iter::once(Self::new(text, tag, name, children))
+            .chain(nodes.into_iter().flatten())
+            .chain(iter::once(close))
+            .collect()
+    }
 
-    // pub fn dynamic_partial(text: &'a str, name: Name<'a>) -> Vec<Node<'a>> {
-    //     name.explode(text, Tag::Section, Tag::Partial, None, None)
-    // }
+    pub fn block(
+        text: &'a str,
+        name: Name<'a>,
+        nodes: Option<Vec<Node<'a>>>,
+        close: Node<'a>,
+    ) -> Vec<Node<'a>> {
+        Self::enclosing(text, Tag::Block, name, nodes, close)
+    }
 
-    // pub fn dynamic_parent(
-    //     text: &'a str,
-    //     name: Name<'a>,
-    //     child: Option<Vec<Node<'a>>>,
-    //     close: Node<'a>,
-    // ) -> Vec<Node<'a>> {
-    //     let child = child.unwrap_or_else(|| Vec::new());
-    //     let mut nodes = Vec::with_capacity(child.len() + 2);
-    //     nodes.push(Node::content(text));
-    //     nodes.extend(child);
-    //     nodes.push(close);
-
-    //     name.explode(text, Tag::Section, Tag::Parent, Some(nodes), None)
-    // }
+    pub fn parent(
+        text: &'a str,
+        name: Name<'a>,
+        nodes: Option<Vec<Node<'a>>>,
+        close: Node<'a>,
+    ) -> Vec<Node<'a>> {
+        Self::enclosing(text, Tag::Parent, name, nodes, close)
+    }
+
+    pub fn dynamic_partial(text: &'a str, indent: &'a str, name: Name<'a>) -> Self {
+        Self {
+            dynamic: true,
+            ..Self::partial(text, indent, name)
+        }
+    }
+
+    pub fn dynamic_parent(
+        text: &'a str,
+        name: Name<'a>,
+        nodes: Option<Vec<Node<'a>>>,
+        close: Node<'a>,
+    ) -> Vec<Node<'a>> {
+        let mut nodes = Self::parent(text, name, nodes, close);
+        nodes[0].dynamic = true;
+        nodes
+    }
 
     // pub fn span(&self) -> (usize, usize) {
     //     // let start = (self.data >> 32) as usize;
@@ -332,28 +427,16 @@ impl PartialEq<Name<'_>> for Name<'_> {
 // This is used when displaying errors to the user.
 impl fmt::Display for Name<'_> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        if self.keys.is_empty() {
-            return write!(f, "Name()");
-        }
-
-        write!(f, "Name({}", self.keys[0])?;
-
-        for key in &self.keys[1..] {
-            write!(f, ".{}", key)?;
-        }
-
-        write!(f, ")")?;
-
-        Ok(())
+        f.write_str(&self.keys.join("."))
     }
 }
 
 impl Spanned for Name<'_> {
     fn span(&self) -> (usize, usize) {
-        let dot = self.keys.len() - 1;
-        let end = self.keys.iter().map(|s| s.len()).sum::<usize>() + dot;
+        let dots = self.keys.len().saturating_sub(1);
+        let len = self.keys.iter().map(|s| s.len()).sum::<usize>() + dots;
 
-        (self.start, end)
+        (self.start, self.start + len)
     }
 }
 
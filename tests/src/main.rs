@@ -1,3 +1,6 @@
+#[cfg(test)]
+mod render;
+#[cfg(test)]
 mod spec;
 
 fn main() {}
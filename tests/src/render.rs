@@ -0,0 +1,1024 @@
+use std::collections::BTreeMap;
+
+use pistachio::{
+    conformance::Spec,
+    Displayed,
+    Error,
+    Lazy,
+    Pistachio,
+    Render,
+    Serialized,
+    Template,
+    Truthiness,
+    Value,
+};
+use serde::Serialize;
+use serde_json::json;
+
+fn render<T: Render>(strict: bool, source: &'static str, vars: &T) -> Result<String, Error> {
+    let mut builder = Pistachio::builder().in_memory();
+    if !strict {
+        builder = builder.missing_is_false();
+    }
+
+    builder.build()?.add("test", source)?.render(vars)
+}
+
+#[test]
+fn test_strict_missing_variable() {
+    let vars = json!({ "a": { "b": {} } });
+
+    match render(true, "Hello {{a.b.c}}!", &vars) {
+        Err(Error::MissingVariable(span, name)) => {
+            assert_eq!(span, (8, 13));
+            assert_eq!(name, "a.b.c");
+        },
+        other => panic!("expected a missing variable error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_strict_missing_section() {
+    let vars = json!({});
+
+    assert!(matches!(
+        render(true, "{{#items}}{{.}}{{/items}}", &vars),
+        Err(Error::MissingVariable(_, name)) if name == "items"
+    ));
+}
+
+#[test]
+fn test_strict_missing_inverted_is_falsey() {
+    let vars = json!({});
+
+    assert_eq!(
+        render(true, "{{^items}}none{{/items}}", &vars).unwrap(),
+        "none"
+    );
+}
+
+#[test]
+fn test_missing_is_false() {
+    let vars = json!({ "a": { "b": {} }, "b": { "c": "ERROR" } });
+
+    assert_eq!(
+        render(false, "[{{x}}{{#a}}{{b.c}}{{/a}}]", &vars).unwrap(),
+        "[]"
+    );
+}
+
+#[test]
+fn test_dotted_names_context_precedence() {
+    let vars = json!({ "a": { "b": {} }, "b": { "c": "ERROR" } });
+
+    assert!(matches!(
+        render(true, "{{#a}}{{b.c}}{{/a}}", &vars),
+        Err(Error::MissingVariable(_, name)) if name == "b.c"
+    ));
+
+    // Serialized values resolve precedence from their keys alone.
+    assert!(matches!(
+        render(true, "{{#a}}{{b.c}}{{/a}}", &Serialized(&vars)),
+        Err(Error::MissingVariable(_, name)) if name == "b.c"
+    ));
+    assert!(matches!(
+        render(
+            true,
+            "{{#a}}{{b.1}}{{/a}}",
+            &Serialized(json!({ "a": { "b": [1] }, "b": [1, 2] }))
+        ),
+        Err(Error::MissingVariable(_, name)) if name == "b.1"
+    ));
+
+    let vars = json!({ "a": { "b": [1] }, "b": { "length": { "c": "ERROR" } } });
+    assert!(matches!(
+        render(true, "{{#a}}{{b.length.c}}{{/a}}", &vars),
+        Err(Error::MissingVariable(_, name)) if name == "b.length.c"
+    ));
+}
+
+#[derive(Debug, Render)]
+struct Page<'a> {
+    title: &'a str,
+    author: Author<'a>,
+}
+
+#[derive(Debug, Render)]
+struct Author<'a> {
+    name: &'a str,
+}
+
+#[test]
+fn test_derive_dotted_names() {
+    let vars = Page {
+        title: "Mustache",
+        author: Author { name: "Chris" },
+    };
+
+    assert_eq!(
+        render(
+            true,
+            "{{title}} by {{author.name}}{{#author}} ({{name}}){{/author}}",
+            &vars
+        )
+        .unwrap(),
+        "Mustache by Chris (Chris)"
+    );
+
+    assert!(matches!(
+        render(true, "{{author.email}}", &vars),
+        Err(Error::MissingVariable(_, name)) if name == "author.email"
+    ));
+}
+
+#[test]
+fn test_parent_paths() {
+    let pistachio = Pistachio::builder().in_memory().build().unwrap();
+    pistachio
+        .add("layouts/base.html", "<{{$body}}default{{/body}}>")
+        .unwrap();
+
+    let template = pistachio
+        .add(
+            "page",
+            "{{< layouts/base.html }}{{$body}}hi{{/body}}{{/ layouts/base.html }}",
+        )
+        .unwrap();
+    assert_eq!(template.render(&json!({})).unwrap(), "<hi>");
+
+    assert!(matches!(
+        pistachio.add("other", "{{<layouts/base.html}}{{/layouts.base.html}}"),
+        Err(Error::ParsingFailed(..))
+    ));
+}
+
+#[test]
+fn test_missing_dynamic_names() {
+    let build = |strict: bool| {
+        let mut builder = Pistachio::builder().in_memory();
+        if !strict {
+            builder = builder.missing_is_false();
+        }
+
+        let pistachio = builder.build().unwrap();
+        pistachio.add("greeting", "hello").unwrap();
+        pistachio
+    };
+
+    let missing_name = json!({});
+    let missing_template = json!({ "name": "farewell" });
+
+    // Neither is an error unless missing names are raised.
+    let pistachio = build(false);
+    let template = pistachio.add("test", "[{{>*name}}]").unwrap();
+    assert_eq!(template.render(&missing_name).unwrap(), "[]");
+    assert_eq!(template.render(&missing_template).unwrap(), "[]");
+
+    let pistachio = build(true);
+    let template = pistachio.add("test", "[{{>*name}}]").unwrap();
+    assert!(matches!(
+        template.render(&missing_name),
+        Err(Error::MissingVariable(_, name)) if name == "name"
+    ));
+    assert!(matches!(
+        template.render(&missing_template),
+        Err(Error::MissingTemplate(name)) if name == "farewell"
+    ));
+}
+
+#[test]
+fn test_section_lambda_source() {
+    let vars = pistachio::vars! {
+        lambda: Value::section_lambda(|source| format!("{:?}", source)),
+    };
+
+    assert_eq!(
+        render(true, "{{#lambda}}\n  x\n  {{/lambda}}\n", &vars).unwrap(),
+        r#""\n  x\n  ""#
+    );
+}
+
+#[test]
+fn test_comments() {
+    let vars = json!({});
+
+    assert_eq!(
+        render(true, "a{{!}}b{{! } }}c\n  {{!\n  License\n}}\nd", &vars).unwrap(),
+        "abc\nd"
+    );
+}
+
+#[test]
+fn test_conformance_report() {
+    let spec = Spec::from_json(
+        r#"{
+            "tests": [
+                { "name": "pass", "data": { "a": 1 }, "template": "{{a}}", "expected": "1" },
+                { "name": "fail", "data": {}, "template": "x\n{{a}}", "expected": "x\ny" },
+                { "name": "error", "template": "{{#a}}", "expected": "" }
+            ]
+        }"#,
+    )
+    .unwrap();
+
+    let report = spec.run();
+
+    assert_eq!((report.passed(), report.failed()), (1, 2));
+    assert_eq!(
+        report.to_string().lines().take(5).collect::<Vec<_>>(),
+        [
+            "PASS pass",
+            "FAIL fail",
+            r#"    "x\n""#,
+            r#"  - "y""#,
+            "ERROR error"
+        ]
+    );
+}
+
+#[test]
+fn test_conformance_yaml() {
+    let report = Spec::load("spec/specs/comments.yml").unwrap().run();
+
+    assert!(report.is_success(), "{}", report);
+}
+
+#[derive(Serialize)]
+struct Order {
+    id: u32,
+    status: Status,
+    note: Option<&'static str>,
+    customer: Customer,
+    items: Vec<Item>,
+    totals: BTreeMap<u8, f64>,
+}
+
+#[derive(Serialize)]
+struct Customer {
+    name: &'static str,
+}
+
+#[derive(Serialize)]
+struct Item {
+    name: &'static str,
+    quantity: u32,
+}
+
+#[derive(Serialize)]
+enum Status {
+    Shipped,
+}
+
+#[test]
+fn test_serialized() {
+    let vars = Serialized(Order {
+        id: 7,
+        status: Status::Shipped,
+        note: None,
+        customer: Customer { name: "<Ada>" },
+        items: vec![
+            Item {
+                name: "tea",
+                quantity: 2,
+            },
+            Item {
+                name: "cake",
+                quantity: 0,
+            },
+        ],
+        totals: BTreeMap::from([(1, 2.5)]),
+    });
+
+    assert_eq!(
+        render(
+            true,
+            "#{{id}} {{status}} {{customer.name}}{{^note}}!{{/note}} {{totals.1}}\n\
+             {{#items}}{{name}}{{#quantity}} x{{quantity}}{{/quantity}};{{/items}}\n\
+             {{#customer}}{{{name}}}{{/customer}}",
+            &vars
+        )
+        .unwrap(),
+        "#7 Shipped &lt;Ada&gt;! 2.5\ntea x2;cake;\n<Ada>"
+    );
+
+    assert!(matches!(
+        render(true, "{{customer.email}}", &vars),
+        Err(Error::MissingVariable(_, name)) if name == "customer.email"
+    ));
+}
+
+#[test]
+fn test_yaml_values() {
+    let vars: serde_yaml::Value = serde_yaml::from_str(
+        "
+        title: Docs
+        count: 12345678901234567
+        empty: []
+        zero: 0.0
+        pages: [{ name: a }, { name: b }]
+        kind: !Guide intro
+        ",
+    )
+    .unwrap();
+
+    assert_eq!(
+        render(
+            true,
+            "{{title}} {{count}} {{{count}}} {{kind}}{{^empty}}!{{/empty}}{{^zero}}0{{/zero}}\
+             {{#zero}}?{{/zero}}{{#count}}{{.}}{{/count}} {{#pages}}{{name}}{{/pages}}{{pages.length}}",
+            &vars
+        )
+        .unwrap(),
+        "Docs 12345678901234567 12345678901234567 intro!012345678901234567 ab2"
+    );
+}
+
+#[test]
+fn test_toml_values() {
+    let vars: toml::Value = toml::from_str(
+        r#"
+        title = "Docs"
+        published = 1979-05-27T07:32:00Z
+        draft = false
+
+        [[pages]]
+        name = "a"
+
+        [[pages]]
+        name = "b"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        render(
+            true,
+            "{{title}} {{published}}{{^draft}}!{{/draft}} {{#pages}}{{name}}{{/pages}}{{pages.1.name}}{{pages.length}} \
+             {{#published}}{{{.}}}{{/published}}",
+            &vars
+        )
+        .unwrap(),
+        "Docs 1979-05-27T07:32:00Z! abb2 1979-05-27T07:32:00Z"
+    );
+}
+
+// Covers the `HashSet` impl itself, so the std type is used on purpose.
+#[test]
+#[allow(clippy::disallowed_types)]
+fn test_collections() {
+    use std::collections::{
+        BTreeSet,
+        HashSet,
+        LinkedList,
+        VecDeque,
+    };
+
+    use indexmap::IndexMap;
+
+    let mut vars = IndexMap::new();
+    vars.insert(
+        "set",
+        Box::new(BTreeSet::from(["b", "a"])) as Box<dyn Render>,
+    );
+    vars.insert("deque", Box::new(VecDeque::from([1, 2])));
+    vars.insert("list", Box::new(LinkedList::<u8>::new()));
+    vars.insert("pair", Box::new(("x", 2.5)));
+    vars.insert("hashed", Box::new(HashSet::from([3, 1, 4, 2])));
+
+    assert_eq!(
+        render(
+            true,
+            "{{#set}}{{.}}{{/set}} {{#deque}}{{.}}{{/deque}}{{^list}}!{{/list}} \
+             {{pair.0}}={{pair.1}} {{#pair}}[{{.}}]{{/pair}} {{#hashed}}{{.}}{{/hashed}}{{hashed.0}}",
+            &vars
+        )
+        .unwrap(),
+        "ab 12! x=2.5 [x][2.5] 12341"
+    );
+
+    assert!(matches!(
+        render(true, "{{pair.2}}", &vars),
+        Err(Error::MissingVariable(_, name)) if name == "pair.2"
+    ));
+}
+
+#[test]
+fn test_lazy_iterators() {
+    use std::cell::Cell;
+
+    use pistachio::{
+        Iter,
+        IterFn,
+    };
+
+    let calls = Cell::new(0);
+    let mut vars = std::collections::BTreeMap::new();
+    vars.insert(
+        "squares",
+        Box::new(Iter((1..4).map(|n| n * n))) as Box<dyn Render>,
+    );
+    vars.insert("none", Box::new(Iter(std::iter::empty::<u8>())));
+    vars.insert(
+        "rows",
+        Box::new(IterFn(|| {
+            calls.set(calls.get() + 1);
+            vec!["a", "b"]
+        })),
+    );
+
+    assert_eq!(
+        render(
+            true,
+            "{{#squares}}{{.}},{{/squares}}{{^none}}!{{/none}}{{#rows}}{{.}}{{/rows}}",
+            &vars
+        )
+        .unwrap(),
+        "1,4,9,!ab"
+    );
+    assert_eq!(calls.get(), 1);
+}
+
+#[test]
+fn test_positional_access() {
+    let vars = json!({ "items": [{ "name": "a" }, { "name": "b" }], "empty": [] });
+    let template = "{{items.1.name}} {{items.length}} {{#items.0}}{{name}}{{/items.0}}\
+                    {{^empty.length}}!{{/empty.length}}";
+
+    assert_eq!(render(true, template, &vars).unwrap(), "b 2 a!");
+    assert_eq!(
+        render(true, template, &Serialized(&vars)).unwrap(),
+        "b 2 a!"
+    );
+
+    assert!(matches!(
+        render(true, "{{items.2.name}}", &vars),
+        Err(Error::MissingVariable(_, name)) if name == "items.2.name"
+    ));
+}
+
+#[test]
+fn test_iteration_metadata() {
+    let vars = json!({ "items": ["a", "b", "c"] });
+    let template = "{{#items}}{{@index}}{{@number}}{{.}}{{#@first}}^{{/@first}}\
+                    {{#@odd}}o{{/@odd}}{{#@even}}e{{/@even}}{{^@last}},{{/@last}}{{/items}}";
+
+    assert_eq!(render(true, template, &vars).unwrap(), "01a^o,12be,23co");
+    assert_eq!(
+        render(true, template, &Serialized(&vars)).unwrap(),
+        "01a^o,12be,23co"
+    );
+}
+
+#[test]
+// Entries sort a `HashMap` to render it deterministically.
+#[allow(clippy::disallowed_types)]
+fn test_map_entries() {
+    use std::collections::HashMap;
+
+    use pistachio::{
+        Entries,
+        Order,
+    };
+
+    let labels = HashMap::from([("b", 2), ("a", 1), ("c", 3)]);
+    let json = json!({ "z": { "n": 1 }, "y": { "n": 2 } });
+    let json = json.as_object().unwrap();
+    let index = indexmap::IndexMap::from([("z", 1), ("y", 2)]);
+
+    let mut vars = BTreeMap::new();
+    vars.insert("labels", Box::new(Entries::new(&labels)) as Box<dyn Render>);
+    vars.insert("json", Box::new(Entries::new(json)));
+    vars.insert(
+        "index",
+        Box::new(Entries::with_order(&index, Order::Natural)),
+    );
+
+    assert_eq!(
+        render(
+            true,
+            "{{#labels}}{{key}}={{value}}{{^@last}},{{/@last}}{{/labels}} \
+             {{#json}}{{key}}{{value.n}}{{/json}} {{#index}}{{key}}{{value}}{{/index}}",
+            &vars
+        )
+        .unwrap(),
+        "a=1,b=2,c=3 y2z1 z1y2"
+    );
+
+    // An entry owns `key` and `value`, so a dotted name through them doesn't
+    // fall through to outer frames.
+    vars.insert("value", Box::new(json!({ "x": "LEAK" })));
+    assert!(matches!(
+        render(true, "{{#index}}{{value.x}}{{/index}}", &vars),
+        Err(Error::MissingVariable(_, name)) if name == "value.x"
+    ));
+    assert_eq!(
+        render(false, "[{{#index}}{{value.x}}{{/index}}]", &vars).unwrap(),
+        "[]"
+    );
+}
+
+#[derive(Debug, Render)]
+struct Counter {
+    count: u32,
+}
+
+#[test]
+fn test_std_wrappers() {
+    use std::{
+        borrow::Cow,
+        cell::{
+            Cell,
+            RefCell,
+        },
+        collections::BTreeMap,
+        num::NonZeroU32,
+        path::PathBuf,
+        sync::{
+            Mutex,
+            RwLock,
+        },
+    };
+
+    let ok: Result<&str, std::fmt::Error> = Ok("ok");
+    let err: Result<&str, std::fmt::Error> = Err(std::fmt::Error);
+
+    let mut vars = BTreeMap::new();
+    vars.insert("cow", Box::new(Cow::Borrowed("<cow>")) as Box<dyn Render>);
+    vars.insert("ok", Box::new(ok));
+    vars.insert("err", Box::new(err));
+    vars.insert("cell", Box::new(Cell::new(3)));
+    vars.insert("refcell", Box::new(RefCell::new(vec![1, 2])));
+    vars.insert("mutex", Box::new(Mutex::new(Counter { count: 4 })));
+    vars.insert("rwlock", Box::new(RwLock::new("rw")));
+    vars.insert("char", Box::new('<'));
+    vars.insert("nonzero", Box::new(NonZeroU32::new(5).unwrap()));
+    vars.insert("path", Box::new(PathBuf::from("a/b.txt")));
+
+    assert_eq!(
+        render(
+            true,
+            "{{cow}} {{ok}}{{^err}} [{{.}}]{{/err}} {{cell}} {{refcell.length}} \
+             {{#mutex}}{{count}}{{cell}}{{/mutex}} {{mutex.count}} {{rwlock}} {{char}}{{{char}}} \
+             {{nonzero}} {{path}}",
+            &vars
+        )
+        .unwrap(),
+        "&lt;cow&gt; ok [Error] 3 2 43 4 rw &lt;< 5 a/b.txt"
+    );
+
+    // A lock is held while a section renders through it, so reaching it again
+    // from inside is an error rather than a deadlock, as is a lock held elsewhere.
+    assert!(matches!(
+        render(true, "{{#mutex}}{{mutex.count}}{{/mutex}}", &vars),
+        Err(Error::LockUnavailable)
+    ));
+
+    let mutex = Mutex::new(Counter { count: 4 });
+    let _guard = mutex.lock().unwrap();
+    assert!(matches!(
+        render(true, "{{count}}", &mutex),
+        Err(Error::LockUnavailable)
+    ));
+
+    let cell = RefCell::new(Counter { count: 4 });
+    let _borrow = cell.borrow_mut();
+    assert!(matches!(
+        render(true, "{{count}}", &cell),
+        Err(Error::LockUnavailable)
+    ));
+}
+
+#[derive(Debug, Render)]
+struct Shelf {
+    items: Vec<&'static str>,
+}
+
+#[derive(Debug, Render)]
+struct Library {
+    title: &'static str,
+    shelf: std::sync::Mutex<Shelf>,
+}
+
+#[test]
+fn test_lock_sections_fall_through() {
+    let vars = Library {
+        title: "T",
+        shelf: std::sync::Mutex::new(Shelf {
+            items: vec!["a", "b"],
+        }),
+    };
+
+    // Names inside a section entered through the lock resolve against its
+    // value and outer frames without locking it again.
+    assert_eq!(
+        render(
+            true,
+            "{{#shelf.items}}{{.}}{{title}}{{/shelf.items}} \
+             {{#shelf}}{{items.length}}{{#items}}{{.}}{{title}}{{/items}}{{/shelf}}",
+            &vars
+        )
+        .unwrap(),
+        "aTbT 2aTbT"
+    );
+}
+
+#[test]
+fn test_result_values() {
+    let ok_false: Result<bool, ()> = Ok(false);
+    let ok_empty: Result<Vec<u8>, ()> = Ok(Vec::new());
+    let err: Result<bool, &str> = Err("failed");
+
+    let template = "[{{#.}}yes{{/.}}{{^.}}no{{/.}}]";
+
+    assert_eq!(render(true, template, &ok_false).unwrap(), "[no]");
+    assert_eq!(render(true, template, &ok_empty).unwrap(), "[no]");
+    assert_eq!(
+        render(true, "{{^.}}{{.}}{{/.}}", &err).unwrap(),
+        "&quot;failed&quot;"
+    );
+}
+
+#[test]
+fn test_ecosystem_types() {
+    use std::{
+        collections::BTreeMap,
+        str::FromStr,
+    };
+
+    use chrono::{
+        NaiveDate,
+        TimeZone,
+        Utc,
+    };
+    use pistachio::Formatted;
+    use rust_decimal::Decimal;
+    use time::{
+        format_description,
+        macros::{
+            date,
+            datetime,
+        },
+    };
+    use url::Url;
+    use uuid::Uuid;
+
+    let uuid = Uuid::from_u128(0x1234);
+    let chrono = Utc.with_ymd_and_hms(2024, 2, 29, 12, 30, 0).unwrap();
+    let naive = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+    let day_month = format_description::parse_borrowed::<2>("[day]/[month]").unwrap();
+
+    let mut vars = BTreeMap::new();
+    vars.insert("chrono", Box::new(chrono) as Box<dyn Render>);
+    vars.insert("naive", Box::new(naive));
+    vars.insert("chrono_fmt", Box::new(Formatted(naive, "%d %B %Y")));
+    vars.insert("time", Box::new(datetime!(2024-02-29 12:30 UTC)));
+    vars.insert("date", Box::new(date!(2024 - 02 - 29)));
+    vars.insert(
+        "time_fmt",
+        Box::new(Formatted(date!(2024 - 02 - 29), &day_month[..])),
+    );
+    vars.insert("uuid", Box::new(uuid));
+    vars.insert("simple", Box::new(uuid.simple()));
+    vars.insert(
+        "url",
+        Box::new(Url::parse("https://example.com/?a=1&b=2").unwrap()),
+    );
+    vars.insert("decimal", Box::new(Decimal::from_str("1.50").unwrap()));
+    vars.insert("zero", Box::new(Decimal::ZERO));
+
+    assert_eq!(
+        render(
+            true,
+            "{{chrono}} {{naive}} {{chrono_fmt}}\n{{time}} {{date}} {{time_fmt}}\n\
+             {{uuid}} {{simple}}\n{{url}} {{decimal}}{{^zero}}!{{/zero}}",
+            &vars
+        )
+        .unwrap(),
+        "2024-02-29T12:30:00+00:00 2024-02-29 29 February 2024\n\
+         2024-02-29T12:30:00Z 2024-02-29 29/02\n\
+         00000000-0000-0000-0000-000000001234 00000000000000000000000000001234\n\
+         https://example.com/?a=1&amp;b=2 1.50!"
+    );
+
+    assert!(matches!(
+        render(true, "{{.}}", &Formatted(naive, "%Q")),
+        Err(Error::InvalidFormat(_))
+    ));
+
+    // A date has no time or offset to format.
+    let offset = format_description::parse_borrowed::<2>("[offset_hour]").unwrap();
+    assert!(matches!(
+        render(
+            true,
+            "{{.}}",
+            &Formatted(date!(2024 - 02 - 29), &offset[..])
+        ),
+        Err(Error::InvalidFormat(_))
+    ));
+}
+
+#[test]
+fn test_number_format() {
+    use pistachio::NumberFormat;
+
+    let vars = json!({
+        "sum": 0.1 + 0.2,
+        "big": 12345678901234567_u64,
+        "negative": -1234567.891,
+        "small": 0.5,
+    });
+    let template = "{{sum}} {{big}} {{negative}} {{small}}";
+
+    let render_with = |format: NumberFormat| {
+        Pistachio::builder()
+            .in_memory()
+            .number_format(format)
+            .build()
+            .unwrap()
+            .add("test", template)
+            .unwrap()
+            .render(&vars)
+            .unwrap()
+    };
+
+    assert_eq!(
+        render(true, template, &vars).unwrap(),
+        "0.30000000000000004 12345678901234567 -1234567.891 0.5"
+    );
+    assert_eq!(
+        render_with(NumberFormat::new().significant_digits(15)),
+        "0.3 12345678901234567 -1234567.891 0.5"
+    );
+    assert_eq!(
+        render_with(
+            NumberFormat::new()
+                .decimal_places(2)
+                .thousands_separator('.')
+                .decimal_separator(',')
+        ),
+        "0,30 12.345.678.901.234.567 -1.234.567,89 0,50"
+    );
+    assert_eq!(
+        render_with(
+            NumberFormat::new()
+                .significant_digits(3)
+                .thousands_separator(',')
+        ),
+        "0.3 12,345,678,901,234,567 -1,230,000 0.5"
+    );
+
+    // The format is kept by the template, even once the `Pistachio` is gone.
+    let template = Pistachio::builder()
+        .in_memory()
+        .number_format(NumberFormat::new().decimal_places(1))
+        .build()
+        .unwrap()
+        .add("test", "{{small}}")
+        .unwrap();
+    assert_eq!(template.render(&vars).unwrap(), "0.5");
+    assert_eq!(template.render(&json!({ "small": 0.25 })).unwrap(), "0.2");
+}
+
+#[test]
+fn test_dynamic_values() {
+    let vars = pistachio::vars! {
+        title: "Fish & Chips",
+        count: 3u8,
+        price: 4.5,
+        missing: None::<String>,
+        "html": Value::raw("<b>bold</b>"),
+        items: [1, "two", { name: "three" }],
+        nested: { flag: true, empty: [] },
+        shout: Value::lambda(|| "{{title}}!"),
+        wrap: Value::section_lambda(|source| format!("<{}>", source)),
+    };
+
+    assert_eq!(
+        render(
+            true,
+            "{{title}} {{count}} {{price}} {{html}} {{{html}}} [{{missing}}]",
+            &vars
+        )
+        .unwrap(),
+        "Fish &amp; Chips 3 4.5 <b>bold</b> <b>bold</b> []"
+    );
+    assert_eq!(
+        render(
+            false,
+            "{{#items}}({{.}}{{name}}){{/items}}{{#nested.flag}}yes{{/nested.flag}}{{^nested.empty}}none{{/nested.empty}}",
+            &vars
+        )
+        .unwrap(),
+        "(1)(two)(three)yesnone"
+    );
+    assert_eq!(
+        render(true, "{{{shout}}} {{#wrap}}{{count}}{{/wrap}}", &vars).unwrap(),
+        "Fish &amp; Chips! <3>"
+    );
+}
+
+#[test]
+fn test_display_values() {
+    use std::net::Ipv4Addr;
+
+    #[derive(Debug, Render)]
+    struct Host {
+        #[pistachio(display)]
+        address: Ipv4Addr,
+        #[pistachio(debug)]
+        tags: Vec<&'static str>,
+    }
+
+    let host = Host {
+        address: Ipv4Addr::new(10, 0, 0, 1),
+        tags: vec!["a<b"],
+    };
+
+    assert_eq!(
+        render(true, "{{address}} {{tags}} {{{tags}}}", &host).unwrap(),
+        "10.0.0.1 [&quot;a&lt;b&quot;] [\"a<b\"]"
+    );
+    assert_eq!(
+        render(true, "{{#address}}up{{/address}}", &host).unwrap(),
+        "up"
+    );
+
+    let template = Template::new("Hello {{name}}!").unwrap();
+    let vars = BTreeMap::from([("name", Displayed('&'))]);
+
+    assert_eq!(format!("[{}]", template.display(&vars)), "[Hello &amp;!]");
+
+    // A rendering error is written in place of the output, rather than
+    // making `format!` panic.
+    let template = Pistachio::builder()
+        .in_memory()
+        .build()
+        .unwrap()
+        .add("test", "Hello {{missing}}!")
+        .unwrap();
+    let err = template.render(&vars).unwrap_err();
+
+    assert!(matches!(err, Error::MissingVariable(..)));
+    assert_eq!(format!("{}", template.display(&vars)), err.to_string());
+}
+
+#[test]
+fn test_lazy_values() {
+    use std::cell::Cell;
+
+    let calls = Cell::new(0);
+    let sidebar = Lazy::new(|| {
+        calls.set(calls.get() + 1);
+        vec!["home", "about"]
+    });
+    let unused = Lazy::new(|| -> String { panic!("unused values are never computed") });
+    let vars = BTreeMap::from([
+        ("sidebar", &sidebar as &dyn Render),
+        ("unused", &unused as &dyn Render),
+    ]);
+
+    assert_eq!(
+        render(
+            true,
+            "{{#sidebar}}[{{.}}]{{/sidebar}}{{^sidebar}}none{{/sidebar}} {{sidebar.length}}",
+            &vars
+        )
+        .unwrap(),
+        "[home][about] 2"
+    );
+    assert_eq!(calls.get(), 1);
+
+    render(true, "{{#sidebar}}{{.}}{{/sidebar}}", &vars).unwrap();
+    assert_eq!(calls.get(), 1);
+
+    let empty = Lazy::new(String::new);
+    assert_eq!(
+        render(true, "{{#.}}yes{{/.}}{{^.}}no{{/.}}", &empty).unwrap(),
+        "no"
+    );
+}
+
+#[test]
+fn test_truthiness() {
+    let vars = json!({
+        "zero": 0,
+        "zero_string": "0",
+        "list": [],
+        "map": {},
+        "tiny": 1e-20,
+        "empty": "",
+    });
+    let template = "{{^zero}}a{{/zero}}{{^zero_string}}b{{/zero_string}}{{^list}}c{{/list}}\
+                    {{^map}}d{{/map}}{{^tiny}}e{{/tiny}}{{^empty}}f{{/empty}}";
+
+    let render_with = |truthiness: Truthiness| {
+        let pistachio = Pistachio::builder()
+            .in_memory()
+            .truthiness(truthiness)
+            .build()
+            .unwrap();
+        let template = pistachio.add("test", template).unwrap();
+
+        (
+            template.render(&vars).unwrap(),
+            template.render(&Serialized(&vars)).unwrap(),
+        )
+    };
+
+    assert_eq!(
+        render_with(Truthiness::new()),
+        ("acdef".into(), "acdef".into())
+    );
+    assert_eq!(
+        render_with(Truthiness::javascript()),
+        ("acf".into(), "acf".into())
+    );
+    assert_eq!(
+        render_with(
+            Truthiness::new()
+                .zero(true)
+                .zero_string(false)
+                .empty_string(true)
+        ),
+        ("bcd".into(), "bcd".into())
+    );
+
+    // The policy is kept by the template, even once the `Pistachio` is gone.
+    let template = Pistachio::builder()
+        .in_memory()
+        .truthiness(Truthiness::javascript())
+        .build()
+        .unwrap()
+        .add("test", template)
+        .unwrap();
+    assert_eq!(template.render(&vars).unwrap(), "acf");
+}
+
+#[test]
+fn test_lookup() {
+    use pistachio::render::{
+        Context,
+        Writer,
+    };
+
+    // Greets whoever `user.name` resolves to where it's rendered.
+    #[derive(Debug)]
+    struct Greeting;
+
+    impl Render for Greeting {
+        fn render_escaped(&self, context: Context, writer: &mut Writer) -> Result<(), Error> {
+            writer.write_escaped("Hello ")?;
+
+            match context.lookup("user.name") {
+                Some(name) => name.render_escaped(context, writer),
+                None => writer.write_escaped("stranger"),
+            }
+        }
+    }
+
+    fn shout(name: &&str, _context: Context, writer: &mut Writer) -> Result<(), Error> {
+        writer.write_escaped(&name.to_uppercase())
+    }
+
+    #[derive(Debug, Render)]
+    struct User {
+        name: &'static str,
+        #[pistachio(rename = "loud", callback = shout)]
+        shouted: &'static str,
+        #[pistachio(display)]
+        address: std::net::Ipv4Addr,
+    }
+
+    #[derive(Debug, Render)]
+    struct Page {
+        greeting: Greeting,
+        #[pistachio(flatten)]
+        meta: BTreeMap<&'static str, &'static str>,
+        user: User,
+    }
+
+    let page = Page {
+        greeting: Greeting,
+        meta: BTreeMap::from([("title", "Home")]),
+        user: User {
+            name: "Ada <3",
+            shouted: "quiet",
+            address: std::net::Ipv4Addr::LOCALHOST,
+        },
+    };
+
+    assert!(page.lookup("title").is_some());
+    assert!(page.lookup("user").unwrap().lookup("address").is_some());
+    assert!(page.lookup("missing").is_none());
+
+    assert_eq!(
+        render(
+            true,
+            "{{title}}: {{greeting}}, {{user.loud}} {{#user}}{{address}}{{/user}}",
+            &page
+        )
+        .unwrap(),
+        "Home: Hello Ada &lt;3, QUIET 127.0.0.1"
+    );
+    assert_eq!(render(true, "{{.}}", &Greeting).unwrap(), "Hello stranger");
+}